@@ -0,0 +1,117 @@
+#![feature(test)]
+
+extern crate test;
+
+use syzygy::model::Model;
+use syzygy::prelude::*;
+use test::Bencher;
+
+#[derive(Debug, Clone)]
+struct BenchModel {
+    counter: i64,
+}
+
+impl Model for BenchModel {
+    type Snapshot = Self;
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+}
+
+const BATCH: usize = 1_000;
+
+fn syzygy() -> Syzygy<BenchModel> {
+    Syzygy::builder().model(BenchModel { counter: 0 }).build()
+}
+
+#[bench]
+fn dispatch_empty(b: &mut Bencher) {
+    let mut syzygy = syzygy();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            syzygy.dispatch(|_: &mut Syzygy<BenchModel>| {});
+        }
+        syzygy.handle_effects();
+    });
+}
+
+#[bench]
+fn handle_effects(b: &mut Bencher) {
+    let mut syzygy = syzygy();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            syzygy.dispatch(|cx: &mut Syzygy<BenchModel>| cx.model_mut().counter += 1);
+        }
+        syzygy.handle_effects();
+    });
+}
+
+#[bench]
+fn model_update(b: &mut Bencher) {
+    let mut syzygy = syzygy();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            syzygy.update(|m| m.counter += 1);
+        }
+    });
+}
+
+#[bench]
+fn handle_effects_chained(b: &mut Bencher) {
+    let mut syzygy = syzygy();
+    b.iter(|| {
+        for _ in 0..BATCH / 4 {
+            syzygy.dispatch(|cx: &mut Syzygy<BenchModel>| {
+                cx.dispatch(|cx| cx.model_mut().counter += 1);
+                cx.dispatch(|cx| cx.model_mut().counter += 1);
+                cx.dispatch(|cx| cx.model_mut().counter += 1);
+            });
+        }
+        syzygy.handle_effects();
+    });
+}
+
+#[bench]
+fn task_spawn(b: &mut Bencher) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let _guard = runtime.enter();
+    let mut syzygy = syzygy();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            syzygy.task(|_| async {});
+        }
+        syzygy.handle_effects();
+    });
+}
+
+#[derive(Clone)]
+struct Table(Vec<u64>);
+
+#[bench]
+fn resource_get(b: &mut Bencher) {
+    let syzygy = Syzygy::builder()
+        .model(BenchModel { counter: 0 })
+        .resource(Table(vec![0; 256]))
+        .build();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            test::black_box(syzygy.resource::<Table>().0);
+        }
+    });
+}
+
+#[bench]
+fn resource_cached(b: &mut Bencher) {
+    let syzygy = Syzygy::builder()
+        .model(BenchModel { counter: 0 })
+        .resource(Table(vec![0; 256]))
+        .build();
+    b.iter(|| {
+        for _ in 0..BATCH {
+            test::black_box(syzygy.resource_cached::<Table>());
+        }
+    });
+}
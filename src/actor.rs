@@ -0,0 +1,211 @@
+use std::sync::{Arc, Weak};
+
+use crossbeam_channel::{Receiver, SendError, Sender};
+
+use crate::{
+    context::{FromContext, r#async::AsyncContext},
+    model::Model,
+    runtime::RuntimeAccess,
+    syzygy::Syzygy,
+};
+
+/// Stateful worker that handles messages one at a time on its own thread.
+pub trait Actor<M: Model>: Send + 'static {
+    type Message: Send + 'static;
+
+    fn handle(&mut self, message: Self::Message, outbox: &AsyncContext<M>);
+
+    /// Called once after the actor stopped receiving messages.
+    fn stopped(&mut self, _outbox: &AsyncContext<M>) {}
+}
+
+/// Sends messages to a running actor. The actor stops once every address is dropped.
+#[derive(Debug)]
+pub struct Address<T> {
+    inbox: Sender<T>,
+    stop: Sender<()>,
+}
+
+impl<T> Clone for Address<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inbox: self.inbox.clone(),
+            stop: self.stop.clone(),
+        }
+    }
+}
+
+impl<T> Address<T> {
+    pub fn send(&self, message: T) -> Result<(), SendError<T>> {
+        self.inbox.send(message)
+    }
+
+    /// Ask the actor to stop after the message it is currently handling.
+    pub fn stop(&self) {
+        stop_actor(&self.stop);
+    }
+}
+
+/// A full or disconnected stop channel means the actor is already stopping.
+pub(crate) fn stop_actor(stop: &Sender<()>) {
+    let _ = stop.try_send(());
+}
+
+/// Stop handle kept by `Syzygy` so `shutdown` reaches every actor still running.
+#[derive(Debug)]
+pub(crate) struct ActorStop {
+    stop: Sender<()>,
+    running: Weak<()>,
+}
+
+impl ActorStop {
+    pub(crate) fn stop(&self) {
+        stop_actor(&self.stop);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.running.strong_count() == 0
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    /// Run `actor` on a blocking thread until its addresses are dropped, it is
+    /// stopped through one of them, or `shutdown` is called.
+    pub fn spawn_actor<A>(&mut self, mut actor: A) -> Address<A::Message>
+    where
+        A: Actor<M>,
+    {
+        let (inbox, messages) = crossbeam_channel::unbounded();
+        let (stop, stopped) = crossbeam_channel::bounded(1);
+        let outbox = AsyncContext::from_context(self);
+        let running = Arc::new(());
+        self.actors.retain(|actor| !actor.is_finished());
+        self.actors.push(ActorStop {
+            stop: stop.clone(),
+            running: Arc::downgrade(&running),
+        });
+        self.spawner()
+            .spawn_blocking(move || run_actor(&mut actor, &messages, &stopped, running, &outbox));
+        Address { inbox, stop }
+    }
+}
+
+fn run_actor<M, A>(
+    actor: &mut A,
+    messages: &Receiver<A::Message>,
+    stopped: &Receiver<()>,
+    running: Arc<()>,
+    outbox: &AsyncContext<M>,
+) where
+    M: Model,
+    A: Actor<M>,
+{
+    loop {
+        crossbeam_channel::select_biased! {
+            recv(stopped) -> _ => break,
+            recv(messages) -> message => match message {
+                Ok(message) => actor.handle(message, outbox),
+                Err(_) => break,
+            },
+        }
+    }
+    drop(running);
+    actor.stopped(outbox);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, increment},
+    };
+    use tokio::sync::oneshot;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_actor() {
+        use std::time::Duration;
+
+        struct Summer {
+            total: i32,
+            done: Option<oneshot::Sender<i32>>,
+        }
+
+        impl Actor<TestModel> for Summer {
+            type Message = i32;
+
+            fn handle(&mut self, message: i32, outbox: &AsyncContext<TestModel>) {
+                self.total += message;
+                outbox.dispatch(increment);
+            }
+
+            fn stopped(&mut self, _outbox: &AsyncContext<TestModel>) {
+                if let Some(done) = self.done.take() {
+                    let _ = done.send(self.total);
+                }
+            }
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let (done_tx, done_rx) = oneshot::channel();
+        let address = syzygy.spawn_actor(Summer {
+            total: 0,
+            done: Some(done_tx),
+        });
+
+        syzygy.dispatch(move |_: &mut Syzygy<TestModel>| {
+            for n in 1..=3 {
+                address.send(n).unwrap();
+            }
+        });
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while syzygy.model().counter < 3 {
+                syzygy.handle_effects();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        syzygy.shutdown();
+
+        let total = tokio::time::timeout(Duration::from_secs(5), done_rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(total, 6);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stopped_actors_pruned() {
+        use std::time::Duration;
+
+        struct Idle(Option<oneshot::Sender<()>>);
+
+        impl Actor<TestModel> for Idle {
+            type Message = ();
+
+            fn handle(&mut self, (): (), _outbox: &AsyncContext<TestModel>) {}
+
+            fn stopped(&mut self, _outbox: &AsyncContext<TestModel>) {
+                if let Some(done) = self.0.take() {
+                    let _ = done.send(());
+                }
+            }
+        }
+
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        for _ in 0..3 {
+            let (done_tx, done_rx) = oneshot::channel();
+            syzygy.spawn_actor(Idle(Some(done_tx))).stop();
+            tokio::time::timeout(Duration::from_secs(5), done_rx)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        let _running = syzygy.spawn_actor(Idle(None));
+        assert_eq!(syzygy.actors.len(), 1);
+    }
+}
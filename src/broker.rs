@@ -0,0 +1,237 @@
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    dispatch::{Effect, EffectsTx},
+    model::Model,
+    resource::{ResourceAccess, ResourceModify},
+    syzygy::Syzygy,
+};
+
+pub type Message = Box<dyn Any + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BrokerError {
+    #[error("context is not connected to a broker")]
+    NotConnected,
+    #[error("unknown endpoint `{0}`")]
+    UnknownEndpoint(String),
+    #[error("endpoint `{0}` is disconnected")]
+    Disconnected(String),
+    #[error("endpoint `{0}` is over its memory cap")]
+    Rejected(String),
+}
+
+/// Delivers type-erased messages to one endpoint.
+pub trait Transport: Send + Sync + 'static {
+    fn deliver(&self, endpoint: &str, message: Message) -> Result<(), BrokerError>;
+}
+
+/// In-process transport that hands messages to a `Syzygy` as effects.
+pub struct LocalTransport<M: Model> {
+    effects_tx: EffectsTx<M>,
+}
+
+impl<M: Model> Transport for LocalTransport<M> {
+    fn deliver(&self, endpoint: &str, message: Message) -> Result<(), BrokerError> {
+        let effect = Effect::new(move |syzygy: &mut Syzygy<M>| {
+            if let Some(mailbox) = syzygy.try_resource::<Mailbox<M>>() {
+                mailbox.handle(syzygy, message);
+            }
+        });
+        self.effects_tx.send(effect).map_err(|error| {
+            if error.is_disconnected() {
+                BrokerError::Disconnected(endpoint.to_string())
+            } else {
+                BrokerError::Rejected(endpoint.to_string())
+            }
+        })
+    }
+}
+
+/// Routes typed messages between named endpoints.
+#[derive(Clone, Default)]
+pub struct Broker {
+    endpoints: Arc<RwLock<FxHashMap<String, Arc<dyn Transport>>>>,
+}
+
+impl Broker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `syzygy` under `name` and let it reach other endpoints via `send_to`.
+    pub fn connect<M: Model>(&self, syzygy: &Syzygy<M>, name: impl Into<String>) {
+        self.register(
+            name,
+            LocalTransport {
+                effects_tx: syzygy.effects_bus.tx.clone(),
+            },
+        );
+        syzygy.add_resource(self.clone());
+    }
+
+    pub fn register<T: Transport>(&self, name: impl Into<String>, transport: T) {
+        self.endpoints
+            .write()
+            .expect("Failed to acquire write lock")
+            .insert(name.into(), Arc::new(transport));
+    }
+
+    pub fn disconnect(&self, name: &str) {
+        self.endpoints
+            .write()
+            .expect("Failed to acquire write lock")
+            .remove(name);
+    }
+
+    pub fn send<T>(&self, endpoint: &str, message: T) -> Result<(), BrokerError>
+    where
+        T: Send + Sync + 'static,
+    {
+        let transport = self
+            .endpoints
+            .read()
+            .expect("Failed to acquire read lock")
+            .get(endpoint)
+            .cloned()
+            .ok_or_else(|| BrokerError::UnknownEndpoint(endpoint.to_string()))?;
+        transport.deliver(endpoint, Box::new(message))
+    }
+}
+
+impl fmt::Debug for Broker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let endpoints = self.endpoints.read().expect("Failed to acquire read lock");
+        f.debug_struct("Broker")
+            .field("endpoints", &endpoints.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+type MessageHandler<M> = Arc<dyn Fn(&mut Syzygy<M>, Message) + Send + Sync>;
+
+/// Per-`Syzygy` message handlers, stored as a resource.
+struct Mailbox<M: Model> {
+    handlers: Arc<RwLock<FxHashMap<TypeId, MessageHandler<M>>>>,
+}
+
+impl<M: Model> Clone for Mailbox<M> {
+    fn clone(&self) -> Self {
+        Self {
+            handlers: Arc::clone(&self.handlers),
+        }
+    }
+}
+
+impl<M: Model> Mailbox<M> {
+    fn handle(&self, syzygy: &mut Syzygy<M>, message: Message) {
+        let ty = (*message).type_id();
+        let handler = self
+            .handlers
+            .read()
+            .expect("Failed to acquire read lock")
+            .get(&ty)
+            .cloned();
+        if let Some(handler) = handler {
+            handler(syzygy, message);
+        } else {
+            log::warn!("No handler registered for message {ty:?}");
+        }
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    /// Handle messages of type `T` sent to this instance through a `Broker`.
+    pub fn on_message<T, F>(&self, f: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&mut Syzygy<M>, T) + Send + Sync + 'static,
+    {
+        let mailbox = self.try_resource::<Mailbox<M>>().unwrap_or_else(|| {
+            let mailbox = Mailbox {
+                handlers: Arc::default(),
+            };
+            self.add_resource(mailbox.clone());
+            mailbox
+        });
+        let handler: MessageHandler<M> = Arc::new(move |syzygy, message| {
+            let message = message
+                .downcast::<T>()
+                .expect("Message type should match its TypeId");
+            f(syzygy, *message);
+        });
+        mailbox
+            .handlers
+            .write()
+            .expect("Failed to acquire write lock")
+            .insert(TypeId::of::<T>(), handler);
+    }
+}
+
+pub trait SendMessage: ResourceAccess {
+    /// Send `message` to a named endpoint of the broker this context is connected to.
+    fn send_to<T>(&self, endpoint: &str, message: T) -> Result<(), BrokerError>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.try_resource::<Broker>()
+            .ok_or(BrokerError::NotConnected)?
+            .send(endpoint, message)
+    }
+}
+
+impl<C: ResourceAccess> SendMessage for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_broker() {
+        #[derive(Debug, Clone)]
+        struct OtherModel {
+            log: Vec<String>,
+        }
+
+        impl Model for OtherModel {
+            type Snapshot = Self;
+            fn to_snapshot(&self) -> Self::Snapshot {
+                self.clone()
+            }
+        }
+
+        let broker = Broker::new();
+        let mut window_a: Syzygy<TestModel> =
+            Syzygy::builder().model(TestModel { counter: 0 }).build();
+        let mut window_b: Syzygy<OtherModel> = Syzygy::builder()
+            .model(OtherModel { log: Vec::new() })
+            .build();
+        broker.connect(&window_a, "window-a");
+        broker.connect(&window_b, "window-b");
+
+        window_a.on_message(|cx, amount: i32| cx.model_mut().counter += amount);
+        window_b.on_message(|cx, text: String| cx.model_mut().log.push(text));
+
+        window_a.send_to("window-b", "hello".to_string()).unwrap();
+        window_b.send_to("window-a", 5_i32).unwrap();
+        assert!(matches!(
+            window_a.send_to("window-c", 1_i32),
+            Err(BrokerError::UnknownEndpoint(_))
+        ));
+
+        window_a.handle_effects();
+        window_b.handle_effects();
+
+        assert_eq!(window_a.model().counter, 5);
+        assert_eq!(window_b.model().log, vec!["hello".to_string()]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Marker traits for bounding functions on exactly the access they need.
+//!
+//! ```compile_fail
+//! use syzygy::{capability::CanModifyModel, context::r#async::AsyncContext, model::Model};
+//!
+//! fn reset<C: CanModifyModel>(_cx: &mut C) {}
+//!
+//! fn from_task<M: Model>(mut cx: AsyncContext<M>) {
+//!     reset(&mut cx); // AsyncContext only sees snapshots
+//! }
+//! ```
+//!
+//! ```compile_fail
+//! use syzygy::{model::{Model, ModelModify}, syzygy::Syzygy};
+//!
+//! fn render<M: Model>(syzygy: &Syzygy<M>) {
+//!     let _ = syzygy.view().model_mut(); // views are read-only
+//! }
+//! ```
+
+use crate::{
+    context::r#async::AsyncContext,
+    dispatch::DispatchEffect,
+    model::{Model, ModelAccess, ModelModify, ModelSnapshotAccess},
+    resource::{ResourceAccess, ResourceModify},
+    runtime::RuntimeAccess,
+    syzygy::Syzygy,
+};
+
+pub trait CanReadModel: ModelAccess {}
+
+pub trait CanModifyModel: CanReadModel + ModelModify {}
+
+pub trait CanReadSnapshot: ModelSnapshotAccess {}
+
+pub trait CanReadResources: ResourceAccess {}
+
+pub trait CanModifyResources: CanReadResources + ResourceModify {}
+
+pub trait CanDispatch: DispatchEffect {}
+
+pub trait CanSpawn: RuntimeAccess {}
+
+impl<M: Model> CanReadModel for Syzygy<M> {}
+impl<M: Model> CanModifyModel for Syzygy<M> {}
+impl<M: Model> CanReadResources for Syzygy<M> {}
+impl<M: Model> CanModifyResources for Syzygy<M> {}
+impl<M: Model> CanDispatch for Syzygy<M> {}
+impl<M: Model> CanSpawn for Syzygy<M> {}
+
+impl<M: Model> CanReadSnapshot for AsyncContext<M> {}
+impl<M: Model> CanReadResources for AsyncContext<M> {}
+impl<M: Model> CanDispatch for AsyncContext<M> {}
+impl<M: Model> CanSpawn for AsyncContext<M> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::TestModel;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_capabilities() {
+        fn read_model<C: CanReadModel<Model = TestModel>>(cx: &C) -> i32 {
+            cx.query(|m| m.counter)
+        }
+
+        fn modify_model<C: CanModifyModel<Model = TestModel>>(cx: &mut C) {
+            cx.update(|m| m.counter += 1);
+        }
+
+        fn add_resource<C: CanModifyResources>(cx: &C) {
+            cx.add_resource(42_i32);
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        modify_model(&mut syzygy);
+        add_resource(&syzygy);
+        assert_eq!(read_model(&syzygy), 1);
+        assert_eq!(syzygy.resource::<i32>(), 42);
+    }
+}
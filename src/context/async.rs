@@ -1,14 +1,16 @@
 use std::sync::Arc;
 
 use crate::{
-    dispatch::EffectsTx,
-    model::{Model, ModelSnapshotAccess, ModelSnapshotCreate},
+    dispatch::{CorrelationId, EffectsTx},
+    model::{Model, ModelModify, ModelSnapshotAccess, ModelSnapshotCreate},
     prelude::DispatchEffect,
     resource::{ResourceAccess, Resources},
+    runtime::{RuntimeAccess, Spawner},
     syzygy::Syzygy,
 };
 
 use bon::Builder;
+use tokio::sync::oneshot;
 
 use super::{Context, FromContext};
 
@@ -17,6 +19,39 @@ pub struct AsyncContext<M: Model> {
     model_snapshot: Arc<M::Snapshot>,
     resources: Resources,
     effects_tx: EffectsTx<M>,
+    correlation_id: Option<CorrelationId>,
+    tag: Option<&'static str>,
+    spawner: Spawner,
+}
+
+impl<M: Model> AsyncContext<M> {
+    /// Apply `f` to the model on the loop and resolve once it has run. Errors if the
+    /// update was dropped unrun, e.g. rejected by the memory cap.
+    pub async fn update<F>(&self, f: F) -> Result<(), oneshot::error::RecvError>
+    where
+        F: FnOnce(&mut M) + Send + Sync + 'static,
+    {
+        self.dispatch_await(move |syzygy: &mut Syzygy<M>| syzygy.update(f))
+            .await
+    }
+
+    /// Apply `f` on the loop and resolve with `query` run against the updated model.
+    pub async fn update_and_query<F, Q, R>(
+        &self,
+        f: F,
+        query: Q,
+    ) -> Result<R, oneshot::error::RecvError>
+    where
+        F: FnOnce(&mut M) + Send + Sync + 'static,
+        Q: FnOnce(&M) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.dispatch_await(move |syzygy: &mut Syzygy<M>| {
+            syzygy.update(f);
+            query(&syzygy.model)
+        })
+        .await
+    }
 }
 
 impl<M: Model> Context for AsyncContext<M> {
@@ -26,9 +61,12 @@ impl<M: Model> Context for AsyncContext<M> {
 impl<M: Model> Clone for AsyncContext<M> {
     fn clone(&self) -> Self {
         Self {
-            model_snapshot: self.model_snapshot.clone(),
+            model_snapshot: Arc::clone(&self.model_snapshot),
             resources: self.resources.clone(),
             effects_tx: self.effects_tx.clone(),
+            correlation_id: self.correlation_id,
+            tag: self.tag,
+            spawner: self.spawner.clone(),
         }
     }
 }
@@ -39,10 +77,19 @@ impl<M: Model> FromContext<Syzygy<M>> for AsyncContext<M> {
             model_snapshot: Arc::new(context.model.to_snapshot()),
             resources: context.resources().clone(),
             effects_tx: context.effects_bus.tx.clone(),
+            correlation_id: context.correlation_id(),
+            tag: context.tag(),
+            spawner: context.spawner().clone(),
         }
     }
 }
 
+impl<M: Model> FromContext<AsyncContext<M>> for AsyncContext<M> {
+    fn from_context(context: &AsyncContext<M>) -> Self {
+        context.clone()
+    }
+}
+
 // impl<'a, M: Model> FromContext<'a, EffectContext<'a, M>> for AsyncContext<M> {
 //     fn from_context(context: &'a mut EffectContext<'a, M>) -> Self {
 //         Self {
@@ -74,4 +121,18 @@ impl<M: Model> DispatchEffect for AsyncContext<M> {
     fn effects_tx(&self) -> &EffectsTx<M> {
         &self.effects_tx
     }
+
+    fn correlation_id(&self) -> Option<CorrelationId> {
+        self.correlation_id
+    }
+
+    fn tag(&self) -> Option<&'static str> {
+        self.tag
+    }
+}
+
+impl<M: Model> RuntimeAccess for AsyncContext<M> {
+    fn spawner(&self) -> &Spawner {
+        &self.spawner
+    }
 }
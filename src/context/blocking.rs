@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use crossbeam_channel::RecvTimeoutError;
+
+use crate::{
+    capability::CanDispatch,
+    dispatch::{DispatchEffect, EffectsTx},
+    model::Model,
+    syzygy::Syzygy,
+};
+
+use super::Context;
+
+/// Handle for plain threads and FFI callbacks that only need to dispatch and wait.
+/// Every blocking call gives up after the handle's timeout.
+#[derive(Debug)]
+pub struct BlockingHandle<M: Model> {
+    effects_tx: EffectsTx<M>,
+    timeout: Duration,
+}
+
+impl<M: Model> Clone for BlockingHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            effects_tx: self.effects_tx.clone(),
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    #[must_use]
+    pub fn blocking_handle(&self, timeout: Duration) -> BlockingHandle<M> {
+        BlockingHandle {
+            effects_tx: self.effects_bus.tx.clone(),
+            timeout,
+        }
+    }
+}
+
+impl<M: Model> BlockingHandle<M> {
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run `effect` on the loop and wait for its result.
+    #[track_caller]
+    pub fn run_blocking<F, R>(&self, effect: F) -> Result<R, RecvTimeoutError>
+    where
+        F: FnOnce(&mut Syzygy<M>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.dispatch_blocking(effect, self.timeout)
+    }
+
+    /// Read from the model as of the moment the loop reaches this request.
+    #[track_caller]
+    pub fn query_blocking<F, R>(&self, f: F) -> Result<R, RecvTimeoutError>
+    where
+        F: FnOnce(&M) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.run_blocking(move |syzygy| f(&syzygy.model))
+    }
+}
+
+impl<M: Model> Context for BlockingHandle<M> {
+    type Model = M;
+}
+
+impl<M: Model> DispatchEffect for BlockingHandle<M> {
+    #[inline]
+    fn effects_tx(&self) -> &EffectsTx<M> {
+        &self.effects_tx
+    }
+}
+
+impl<M: Model> CanDispatch for BlockingHandle<M> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{TestModel, increment};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_blocking_handle() {
+        use std::time::Duration;
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let handle = syzygy.blocking_handle(Duration::from_secs(5));
+
+        let worker = std::thread::spawn(move || {
+            handle.dispatch(increment);
+            handle.run_blocking(increment).unwrap();
+            handle.query_blocking(|m| m.counter).unwrap()
+        });
+        while !worker.is_finished() {
+            syzygy.handle_effects();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(worker.join().unwrap(), 2);
+    }
+}
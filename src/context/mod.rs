@@ -1,6 +1,10 @@
 use crate::model::Model;
 
 pub mod r#async;
+pub mod blocking;
+pub mod view;
+
+pub use syzygy_macros::Context;
 
 pub trait Context: Sized {
     type Model: Model;
@@ -0,0 +1,102 @@
+use crate::{
+    capability::{CanDispatch, CanReadModel, CanReadResources},
+    dispatch::{CorrelationId, DispatchEffect, EffectsTx},
+    model::{Model, ModelAccess},
+    resource::{ResourceAccess, Resources},
+    syzygy::Syzygy,
+};
+
+use super::Context;
+
+/// Read-only borrow of a `Syzygy` for view code. Effects can still be
+/// dispatched, but the model and resources cannot be mutated through it.
+#[derive(Debug)]
+pub struct SyzygyView<'a, M: Model> {
+    syzygy: &'a Syzygy<M>,
+}
+
+impl<M: Model> Clone for SyzygyView<'_, M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M: Model> Copy for SyzygyView<'_, M> {}
+
+impl<M: Model> Syzygy<M> {
+    #[must_use]
+    pub fn view(&self) -> SyzygyView<'_, M> {
+        SyzygyView { syzygy: self }
+    }
+}
+
+impl<M: Model> Context for SyzygyView<'_, M> {
+    type Model = M;
+}
+
+impl<M: Model> ModelAccess for SyzygyView<'_, M> {
+    #[inline]
+    fn model(&self) -> &M {
+        self.syzygy.model()
+    }
+}
+
+impl<M: Model> ResourceAccess for SyzygyView<'_, M> {
+    #[inline]
+    fn resources(&self) -> &Resources {
+        self.syzygy.resources()
+    }
+}
+
+impl<M: Model> DispatchEffect for SyzygyView<'_, M> {
+    #[inline]
+    fn effects_tx(&self) -> &EffectsTx<M> {
+        self.syzygy.effects_tx()
+    }
+
+    #[inline]
+    fn correlation_id(&self) -> Option<CorrelationId> {
+        self.syzygy.correlation_id()
+    }
+
+    #[inline]
+    fn tag(&self) -> Option<&'static str> {
+        self.syzygy.tag()
+    }
+}
+
+impl<M: Model> CanReadModel for SyzygyView<'_, M> {}
+impl<M: Model> CanReadResources for SyzygyView<'_, M> {}
+impl<M: Model> CanDispatch for SyzygyView<'_, M> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{TestModel, TestResource, increment};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_view() {
+        use crate::capability::CanReadModel;
+
+        fn render<C: CanReadModel<Model = TestModel> + DispatchEffect>(cx: &C) -> String {
+            cx.dispatch(increment);
+            format!("counter: {}", cx.query(|m| m.counter))
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .resource(TestResource {
+                name: "view".to_string(),
+            })
+            .build();
+
+        let view = syzygy.view();
+        assert_eq!(view.resource::<TestResource>().name, "view");
+        assert_eq!(render(&view), "counter: 0");
+
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+    }
+}
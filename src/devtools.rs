@@ -0,0 +1,444 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write},
+    panic::Location,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crossbeam_channel::Receiver;
+
+use crate::{
+    dispatch::{Effect, EffectMeta, QueueMemory},
+    model::Model,
+    syzygy::Syzygy,
+};
+
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// One effect's first write to the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelWrite {
+    /// `None` for writes made outside of an effect.
+    pub tag: Option<&'static str>,
+    pub origin: Option<&'static Location<'static>>,
+    pub at: SystemTime,
+    /// Model version after the write.
+    pub version: u64,
+}
+
+/// Bounded timeline of model writes, oldest first.
+#[derive(Debug)]
+pub struct WriteTimeline {
+    writes: VecDeque<ModelWrite>,
+    capacity: usize,
+    current: Option<(Option<&'static str>, &'static Location<'static>)>,
+    recorded: bool,
+}
+
+impl Default for WriteTimeline {
+    fn default() -> Self {
+        Self {
+            writes: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            current: None,
+            recorded: false,
+        }
+    }
+}
+
+impl WriteTimeline {
+    pub fn iter(&self) -> impl Iterator<Item = &ModelWrite> {
+        self.writes.iter()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.writes.clear();
+    }
+
+    /// Keep at most `capacity` writes, dropping the oldest first.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.writes.len() > capacity {
+            self.writes.pop_front();
+        }
+    }
+
+    /// Writes the effect with `tag` made, e.g. to answer who changed a field.
+    pub fn by_tag(&self, tag: &str) -> impl Iterator<Item = &ModelWrite> {
+        self.writes.iter().filter(move |w| w.tag == Some(tag))
+    }
+
+    /// The timeline as a JSON array of `{tag, origin, at_ms, version}` objects.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("[");
+        for (i, write) in self.writes.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let at_ms = write
+                .at
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis());
+            let _ = write!(
+                json,
+                r#"{{"tag":{},"origin":{},"at_ms":{at_ms},"version":{}}}"#,
+                json_string(write.tag),
+                json_string(write.origin.map(ToString::to_string).as_deref()),
+                write.version,
+            );
+        }
+        json.push(']');
+        json
+    }
+
+    pub(crate) fn begin(&mut self, meta: &EffectMeta) {
+        self.current = Some((meta.tag, meta.origin));
+        self.recorded = false;
+    }
+
+    pub(crate) fn end(&mut self) {
+        self.current = None;
+        self.recorded = false;
+    }
+
+    pub(crate) fn record(&mut self, version: u64) {
+        if self.recorded || self.capacity == 0 {
+            return;
+        }
+        if self.writes.len() == self.capacity {
+            self.writes.pop_front();
+        }
+        self.writes.push_back(ModelWrite {
+            tag: self.current.and_then(|(tag, _)| tag),
+            origin: self.current.map(|(_, origin)| origin),
+            at: SystemTime::now(),
+            version,
+        });
+        self.recorded = self.current.is_some();
+    }
+}
+
+fn json_string(value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "null".to_string();
+    };
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl<M: Model> Syzygy<M> {
+    /// Which effects wrote to the model through `model_mut`/`update`, and when.
+    #[must_use]
+    pub fn model_writes(&self) -> &WriteTimeline {
+        &self.model_writes
+    }
+
+    pub fn model_writes_mut(&mut self) -> &mut WriteTimeline {
+        &mut self.model_writes
+    }
+}
+
+/// Queue state shared between a `Syzygy` and its `QueueController`s.
+pub(crate) struct QueueControl<M: Model> {
+    paused: AtomicBool,
+    steps: AtomicUsize,
+    held: Mutex<VecDeque<Effect<M>>>,
+}
+
+impl<M: Model> Default for QueueControl<M> {
+    fn default() -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            steps: AtomicUsize::new(0),
+            held: Mutex::new(VecDeque::new()),
+        }
+    }
+}
+
+impl<M: Model> fmt::Debug for QueueControl<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueueControl")
+            .field("paused", &self.paused)
+            .field("steps", &self.steps)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: Model> QueueControl<M> {
+    /// Whether the loop may take another effect from the queue.
+    pub(crate) fn may_run(&self) -> bool {
+        !self.paused.load(Ordering::Acquire) || self.steps.load(Ordering::Acquire) > 0
+    }
+
+    /// Account for an effect taken from the queue while paused.
+    pub(crate) fn took(&self) {
+        if self.paused.load(Ordering::Acquire) {
+            let _ = self
+                .steps
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1));
+        }
+    }
+
+    pub(crate) fn pop_held(&self) -> Option<Effect<M>> {
+        self.held
+            .lock()
+            .expect("Failed to acquire lock")
+            .pop_front()
+    }
+}
+
+/// Debugger handle for pausing, stepping and editing the effect queue of a `Syzygy`.
+/// Effects queued with `immediate` are not held back by a pause.
+pub struct QueueController<M: Model> {
+    control: Arc<QueueControl<M>>,
+    rx: Receiver<Effect<M>>,
+    memory: Arc<QueueMemory>,
+}
+
+impl<M: Model> Clone for QueueController<M> {
+    fn clone(&self) -> Self {
+        Self {
+            control: Arc::clone(&self.control),
+            rx: self.rx.clone(),
+            memory: Arc::clone(&self.memory),
+        }
+    }
+}
+
+impl<M: Model> fmt::Debug for QueueController<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueueController")
+            .field("control", &self.control)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: Model> QueueController<M> {
+    /// Stop `handle_effects` from taking queued effects.
+    pub fn pause(&self) {
+        self.control.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.control.paused.store(false, Ordering::Release);
+        self.control.steps.store(0, Ordering::Release);
+    }
+
+    /// While paused, let the next `handle_effects` run one more queued effect.
+    pub fn step(&self) {
+        self.control.steps.fetch_add(1, Ordering::AcqRel);
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.control.paused.load(Ordering::Acquire)
+    }
+
+    /// Metadata of the queued effects, in the order they will run.
+    #[must_use]
+    pub fn pending(&self) -> Vec<EffectMeta> {
+        self.hold()
+            .iter()
+            .map(|effect| effect.meta.clone())
+            .collect()
+    }
+
+    /// Drop the queued effects matching `pred` and return how many were dropped.
+    pub fn drain_filtered<F>(&self, mut pred: F) -> usize
+    where
+        F: FnMut(&EffectMeta) -> bool,
+    {
+        let mut held = self.hold();
+        let before = held.len();
+        held.retain(|effect| !pred(&effect.meta));
+        before - held.len()
+    }
+
+    /// Move everything from the channel behind the already held effects.
+    fn hold(&self) -> MutexGuard<'_, VecDeque<Effect<M>>> {
+        let mut held = self.control.held.lock().expect("Failed to acquire lock");
+        held.extend(self.rx.try_iter().inspect(|effect| {
+            self.memory.release(effect.meta.size);
+        }));
+        held
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    #[must_use]
+    pub fn queue_controller(&self) -> QueueController<M> {
+        QueueController {
+            control: Arc::clone(&self.queue_control),
+            rx: (*self.effects_bus.rx).clone(),
+            memory: Arc::clone(&self.effects_bus.tx.memory),
+        }
+    }
+}
+
+/// Effect that held the loop for longer than the `detect_blocking` threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockingEffect {
+    pub origin: &'static Location<'static>,
+    pub tag: Option<&'static str>,
+    pub elapsed: Duration,
+    pub threshold: Duration,
+}
+
+type BlockingFn = Arc<dyn Fn(&BlockingEffect) + Send + Sync>;
+
+/// Times every effect on the loop, see `SyzygyBuilder::detect_blocking`.
+#[derive(Clone)]
+pub(crate) struct BlockingDetector {
+    threshold: Duration,
+    callback: BlockingFn,
+}
+
+impl fmt::Debug for BlockingDetector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingDetector")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BlockingDetector {
+    pub(crate) fn new(threshold: Duration, callback: BlockingFn) -> Self {
+        Self {
+            threshold,
+            callback,
+        }
+    }
+
+    pub(crate) fn check(&self, meta: &EffectMeta, elapsed: Duration) {
+        if elapsed <= self.threshold {
+            return;
+        }
+        let blocking = BlockingEffect {
+            origin: meta.origin,
+            tag: meta.tag,
+            elapsed,
+            threshold: self.threshold,
+        };
+        log::warn!(
+            "Effect dispatched at {} blocked the loop for {elapsed:?}",
+            meta.origin
+        );
+        (self.callback)(&blocking);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, increment},
+    };
+
+    #[cfg(all(not(feature = "parallel"), feature = "devtools"))]
+    #[tokio::test]
+    async fn test_model_writes() {
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        syzygy.dispatch_tagged("increment", increment);
+        syzygy.dispatch(|syzygy: &mut Syzygy<TestModel>| {
+            syzygy.update(|m| m.counter += 1);
+            syzygy.update(|m| m.counter += 1);
+        });
+        syzygy.dispatch(|syzygy: &mut Syzygy<TestModel>| {
+            let _ = syzygy.query(|m| m.counter);
+        });
+        syzygy.handle_effects();
+
+        let writes = syzygy.model_writes();
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes.by_tag("increment").count(), 1);
+        assert!(writes.iter().all(|w| w.origin.is_some()));
+        assert!(
+            writes
+                .to_json()
+                .starts_with(r#"[{"tag":"increment","origin":"src/devtools.rs"#)
+        );
+    }
+
+    #[cfg(all(not(feature = "parallel"), feature = "devtools"))]
+    #[tokio::test]
+    async fn test_queue_controller() {
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        let controller = syzygy.queue_controller();
+        controller.pause();
+        for tag in ["a", "b", "a", "c"] {
+            syzygy.dispatch_tagged(tag, increment);
+        }
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 0);
+
+        let tags = |c: &crate::devtools::QueueController<TestModel>| {
+            c.pending()
+                .iter()
+                .map(|m| m.tag.unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tags(&controller), ["a", "b", "a", "c"]);
+
+        controller.step();
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+        assert_eq!(controller.drain_filtered(|m| m.tag == Some("a")), 1);
+        assert_eq!(tags(&controller), ["b", "c"]);
+
+        controller.resume();
+        syzygy.dispatch_tagged("d", increment);
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 4);
+        assert!(controller.pending().is_empty());
+    }
+
+    #[cfg(all(not(feature = "parallel"), feature = "devtools"))]
+    #[tokio::test]
+    async fn test_detect_blocking() {
+        let blocked = Arc::new(Mutex::new(Vec::new()));
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .detect_blocking(Duration::from_millis(5), {
+                let blocked = Arc::clone(&blocked);
+                move |effect| blocked.lock().unwrap().push(effect.tag)
+            })
+            .build();
+
+        syzygy.dispatch(increment);
+        syzygy.dispatch_tagged("io", |_: &mut Syzygy<TestModel>| {
+            std::thread::sleep(Duration::from_millis(10));
+        });
+        syzygy.handle_effects();
+
+        assert_eq!(*blocked.lock().unwrap(), vec![Some("io")]);
+    }
+}
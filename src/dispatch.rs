@@ -1,28 +1,26 @@
-use std::future::Future;
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    future::Future,
+    panic::Location,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use derive_more::derive::{Deref, DerefMut};
-use tokio::sync::oneshot;
+use rustc_hash::FxHashMap;
+use tokio::sync::{Notify, oneshot};
 
 use crate::context::{Context, FromContext};
 use crate::model::ModelModify;
+use crate::runtime::RuntimeAccess;
 use crate::{model::Model, prelude::AsyncContext, syzygy::Syzygy};
 
 pub trait EffectFn<M: Model>: FnOnce(&mut Syzygy<M>) + Send + Sync + 'static {}
 
-pub struct DispatchContext<'a, M: Model> {
-    syzygy: &'a mut Syzygy<M>,
-}
-
-// impl<M: Model> Context for DispatchContext<'_, M> {
-//     type Model = M;
-// }
-
-// impl<M: Model> FromContext<Syzygy<M>> for DispatchContext<'_, M> {
-//     fn from_context(syzygy: &mut Syzygy<M>) -> Self {
-//         Self { syzygy }
-//     }
-// }
-
 impl<M, F> EffectFn<M> for F
 where
     M: Model,
@@ -30,22 +28,411 @@ where
 {
 }
 
-#[derive(Debug, Deref)]
+/// Id shared by an effect and everything dispatched while it (or a task it spawned) runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CorrelationId(u64);
+
+impl CorrelationId {
+    #[must_use]
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[must_use]
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EffectMeta {
+    pub origin: &'static Location<'static>,
+    pub dispatched_at: Instant,
+    pub correlation_id: Option<CorrelationId>,
+    pub tag: Option<&'static str>,
+    /// May be dropped unrun while the loop is overloaded.
+    pub sheddable: bool,
+    /// Held back until `end_frame` when dispatched inside a frame.
+    pub next_frame: bool,
+    /// Approximate bytes held by the effect while queued, see `Effect::with_size_hint`.
+    pub size: usize,
+    /// Source lane for fair scheduling, set by `EffectsTx::with_lane`.
+    pub lane: Option<&'static str>,
+}
+
+pub struct Effect<M: Model> {
+    pub meta: EffectMeta,
+    f: Box<dyn EffectFn<M>>,
+}
+
+/// Correlation id and tag an effect inherits from the context it is dispatched from.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Lineage {
+    correlation_id: Option<CorrelationId>,
+    tag: Option<&'static str>,
+}
+
+impl Lineage {
+    pub(crate) fn of<C: DispatchEffect>(cx: &C) -> Self {
+        Self {
+            correlation_id: cx.correlation_id(),
+            tag: cx.tag(),
+        }
+    }
+}
+
+impl<M: Model> Effect<M> {
+    #[track_caller]
+    pub fn new<F>(f: F) -> Self
+    where
+        F: EffectFn<M>,
+    {
+        Self {
+            meta: EffectMeta {
+                origin: Location::caller(),
+                dispatched_at: Instant::now(),
+                correlation_id: None,
+                tag: None,
+                sheddable: false,
+                next_frame: false,
+                size: size_of::<F>(),
+                lane: None,
+            },
+            f: Box::new(f),
+        }
+    }
+
+    #[must_use]
+    pub fn with_origin(mut self, origin: &'static Location<'static>) -> Self {
+        self.meta.origin = origin;
+        self
+    }
+
+    #[must_use]
+    pub fn with_correlation_id(mut self, correlation_id: Option<CorrelationId>) -> Self {
+        self.meta.correlation_id = correlation_id;
+        self
+    }
+
+    #[must_use]
+    pub(crate) fn with_lineage(mut self, lineage: Lineage) -> Self {
+        self.meta.correlation_id = lineage.correlation_id;
+        self.meta.tag = lineage.tag;
+        self
+    }
+
+    #[must_use]
+    pub fn with_tag(mut self, tag: &'static str) -> Self {
+        self.meta.tag = Some(tag);
+        self
+    }
+
+    #[must_use]
+    pub fn sheddable(mut self) -> Self {
+        self.meta.sheddable = true;
+        self
+    }
+
+    #[must_use]
+    pub fn next_frame(mut self) -> Self {
+        self.meta.next_frame = true;
+        self
+    }
+
+    /// Count `bytes` of heap data owned by the captures towards the effect's size. Only
+    /// the closure itself is counted otherwise.
+    #[must_use]
+    pub fn with_size_hint(mut self, bytes: usize) -> Self {
+        self.meta.size += bytes;
+        self
+    }
+
+    pub(crate) fn into_parts(self) -> (EffectMeta, Box<dyn EffectFn<M>>) {
+        (self.meta, self.f)
+    }
+}
+
+impl<M: Model> fmt::Debug for Effect<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Effect")
+            .field("meta", &self.meta)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Effect that can absorb later effects of the same type while it is still queued.
+pub trait CoalescingEffect<M: Model>: Send + Sync + 'static {
+    fn merge(&mut self, other: Self)
+    where
+        Self: Sized;
+    fn apply(self, syzygy: &mut Syzygy<M>);
+}
+
+type Coalesced = Arc<Mutex<FxHashMap<TypeId, Box<dyn Any + Send + Sync>>>>;
+
+/// Pending coalesced effect owned by its queued applier. Frees the slot if the applier
+/// is dropped unrun, so later effects of the type don't merge into one that never runs.
+struct CoalescedSlot {
+    coalesced: Coalesced,
+    ty: TypeId,
+    armed: bool,
+}
+
+impl CoalescedSlot {
+    fn take(mut self) -> Option<Box<dyn Any + Send + Sync>> {
+        self.armed = false;
+        self.coalesced
+            .lock()
+            .expect("Failed to acquire lock")
+            .remove(&self.ty)
+    }
+}
+
+impl Drop for CoalescedSlot {
+    fn drop(&mut self) {
+        if self.armed
+            && let Ok(mut pending) = self.coalesced.lock()
+        {
+            pending.remove(&self.ty);
+        }
+    }
+}
+
+/// Which effects `SyzygyBuilder::memory_cap` rejects once the queue is over the cap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CapPolicy {
+    #[default]
+    RejectAll,
+    RejectSheddable,
+}
+
+/// Approximate size of the effects sitting in the channel.
+#[derive(Debug)]
+pub(crate) struct QueueMemory {
+    queued: AtomicUsize,
+    cap: AtomicUsize,
+    sheddable_only: AtomicBool,
+    rejected: AtomicU64,
+}
+
+impl Default for QueueMemory {
+    fn default() -> Self {
+        Self {
+            queued: AtomicUsize::new(0),
+            cap: AtomicUsize::new(usize::MAX),
+            sheddable_only: AtomicBool::new(false),
+            rejected: AtomicU64::new(0),
+        }
+    }
+}
+
+impl QueueMemory {
+    pub(crate) fn set_cap(&self, bytes: usize, policy: CapPolicy) {
+        self.cap.store(bytes, Ordering::Relaxed);
+        self.sheddable_only
+            .store(policy == CapPolicy::RejectSheddable, Ordering::Relaxed);
+    }
+
+    fn reserve(&self, meta: &EffectMeta) -> bool {
+        let cap = if meta.sheddable || !self.sheddable_only.load(Ordering::Relaxed) {
+            self.cap.load(Ordering::Relaxed)
+        } else {
+            usize::MAX
+        };
+        let reserved = self
+            .queued
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |queued| {
+                let total = queued.saturating_add(meta.size);
+                (total <= cap).then_some(total)
+            })
+            .is_ok();
+        if !reserved {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        reserved
+    }
+
+    fn force_reserve(&self, size: usize) {
+        self.queued.fetch_add(size, Ordering::Relaxed);
+    }
+
+    pub(crate) fn release(&self, size: usize) {
+        self.queued.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    pub(crate) fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
+/// Error returned by `EffectsTx::send`, holding the effect that was not queued.
+#[derive(Debug, thiserror::Error)]
+pub enum SendError<M: Model> {
+    #[error("effect receiver is disconnected")]
+    Disconnected(Effect<M>),
+    #[error("effect rejected, the queue is over its memory cap")]
+    Rejected(Effect<M>),
+}
+
+impl<M: Model> SendError<M> {
+    #[must_use]
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self, Self::Disconnected(_))
+    }
+
+    #[must_use]
+    pub fn into_effect(self) -> Effect<M> {
+        match self {
+            Self::Disconnected(effect) | Self::Rejected(effect) => effect,
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct EffectsTx<M: Model> {
-    inner: crossbeam_channel::Sender<Box<dyn EffectFn<M>>>,
+    inner: crossbeam_channel::Sender<Effect<M>>,
+    notify: Arc<Notify>,
+    coalesced: Coalesced,
+    pub(crate) memory: Arc<QueueMemory>,
+    lane: Option<&'static str>,
 }
 
 impl<M: Model> Clone for EffectsTx<M> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            notify: Arc::clone(&self.notify),
+            coalesced: Arc::clone(&self.coalesced),
+            memory: Arc::clone(&self.memory),
+            lane: self.lane,
+        }
+    }
+}
+
+impl<M: Model> EffectsTx<M> {
+    /// Queue the effect and wake a loop waiting in `EffectsRx::notified`. Effects rejected
+    /// by the memory cap are returned and counted in `Stats::effects_rejected`.
+    pub fn send(&self, mut effect: Effect<M>) -> Result<(), SendError<M>> {
+        effect.meta.lane = effect.meta.lane.or(self.lane);
+        if !self.memory.reserve(&effect.meta) {
+            log::warn!(
+                "Effect from {} rejected: queue is over its memory cap",
+                effect.meta.origin
+            );
+            return Err(SendError::Rejected(effect));
+        }
+        self.send_reserved(effect)
+    }
+
+    /// Like `send`, but skips the memory cap. For effects the loop queues again after
+    /// they were already admitted once, e.g. held back for the next frame.
+    pub(crate) fn requeue(&self, effect: Effect<M>) {
+        self.memory.force_reserve(effect.meta.size);
+        self.send_reserved(effect)
+            .expect("Effect receiver should be active");
+    }
+
+    /// Send from a dispatch call. A rejected effect is dropped, `send` already logged it.
+    pub(crate) fn send_or_drop(&self, effect: Effect<M>) {
+        match self.send(effect) {
+            Ok(()) | Err(SendError::Rejected(_)) => {}
+            Err(SendError::Disconnected(_)) => panic!("Effect receiver should be active"),
+        }
+    }
+
+    fn send_reserved(&self, effect: Effect<M>) -> Result<(), SendError<M>> {
+        let size = effect.meta.size;
+        self.inner.send(effect).map_err(|error| {
+            self.memory.release(size);
+            SendError::Disconnected(error.into_inner())
+        })?;
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// A sender whose effects are scheduled in the `label` lane, see
+    /// `SyzygyBuilder::lane_weight`.
+    #[must_use]
+    pub fn with_lane(&self, label: &'static str) -> Self {
+        Self {
+            lane: Some(label),
+            ..self.clone()
         }
     }
+
+    pub(crate) fn wake(&self) {
+        self.notify.notify_one();
+    }
+
+    /// Merge into the queued effect of the same type, or queue `effect` if none is pending.
+    #[track_caller]
+    pub fn send_coalesced<E>(&self, effect: E)
+    where
+        E: CoalescingEffect<M>,
+    {
+        let ty = TypeId::of::<E>();
+        let mut pending = self.coalesced.lock().expect("Failed to acquire lock");
+        if let Some(queued) = pending.get_mut(&ty) {
+            queued
+                .downcast_mut::<E>()
+                .expect("Coalesced effect type should match its TypeId")
+                .merge(effect);
+            return;
+        }
+        pending.insert(ty, Box::new(effect));
+        drop(pending);
+
+        let slot = CoalescedSlot {
+            coalesced: Arc::clone(&self.coalesced),
+            ty,
+            armed: true,
+        };
+        let apply = move |syzygy: &mut Syzygy<M>| {
+            if let Some(queued) = slot.take() {
+                let effect = queued
+                    .downcast::<E>()
+                    .expect("Coalesced effect type should match its TypeId");
+                effect.apply(syzygy);
+            }
+        };
+        self.send_or_drop(Effect::new(apply));
+    }
 }
 
 #[derive(Debug, Deref, DerefMut)]
 pub struct EffectsRx<M: Model> {
-    inner: crossbeam_channel::Receiver<Box<dyn EffectFn<M>>>,
+    #[deref]
+    #[deref_mut]
+    inner: crossbeam_channel::Receiver<Effect<M>>,
+    notify: Arc<Notify>,
+}
+
+impl<M: Model> EffectsRx<M> {
+    /// Resolves once an effect has been sent since the last wakeup.
+    pub async fn notified(&self) {
+        self.notify.notified().await;
+    }
 }
 
 #[derive(Debug)]
@@ -57,9 +444,16 @@ pub struct EffectsBus<M: Model> {
 impl<M: Model> Default for EffectsBus<M> {
     fn default() -> Self {
         let (tx, rx) = crossbeam_channel::unbounded();
+        let notify = Arc::new(Notify::new());
         Self {
-            tx: EffectsTx { inner: tx },
-            rx: EffectsRx { inner: rx },
+            tx: EffectsTx {
+                inner: tx,
+                notify: Arc::clone(&notify),
+                coalesced: Coalesced::default(),
+                memory: Arc::default(),
+                lane: None,
+            },
+            rx: EffectsRx { inner: rx, notify },
         }
     }
 }
@@ -71,21 +465,71 @@ impl<M: Model> EffectsBus<M> {
     }
 }
 
+/// Result of a `task_with_deadline` future, tagged with whether it beat the deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deadline<O> {
+    OnTime(O),
+    Late(O),
+}
+
+impl<O> Deadline<O> {
+    #[must_use]
+    pub fn into_inner(self) -> O {
+        match self {
+            Self::OnTime(output) | Self::Late(output) => output,
+        }
+    }
+
+    #[must_use]
+    pub fn is_late(&self) -> bool {
+        matches!(self, Self::Late(_))
+    }
+}
+
+/// Error returned by a `task_ok` future, passed to the `on_task_failed` handler for `E`.
+#[derive(Debug, Clone)]
+pub struct TaskFailed<E> {
+    pub error: E,
+    pub origin: &'static Location<'static>,
+}
+
+pub(crate) type TaskFailedFn<M, E> = Arc<dyn Fn(&mut Syzygy<M>, TaskFailed<E>) + Send + Sync>;
+
+pub(crate) struct TaskFailedHandler<M: Model, E>(pub(crate) TaskFailedFn<M, E>);
+
+impl<M: Model, E> Clone for TaskFailedHandler<M, E> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
 
 pub trait DispatchEffect: Context {
     fn effects_tx(&self) -> &EffectsTx<Self::Model>;
 
+    /// Correlation id inherited by effects dispatched through this context.
+    #[inline]
+    fn correlation_id(&self) -> Option<CorrelationId> {
+        None
+    }
+
+    /// Tag inherited by effects dispatched through this context.
+    #[inline]
+    fn tag(&self) -> Option<&'static str> {
+        None
+    }
+
     #[inline]
+    #[track_caller]
     fn send_effect<F>(&self, effect: F)
     where
         F: EffectFn<Self::Model> + Send + Sync + 'static,
     {
-        self.effects_tx()
-            .send(Box::new(effect))
-            .expect("Effect receiver should be active");
+        let effect = Effect::new(effect).with_lineage(Lineage::of(self));
+        self.effects_tx().send_or_drop(effect);
     }
 
     #[inline]
+    #[track_caller]
     fn dispatch<F>(&self, effect: F)
     where
         F: EffectFn<Self::Model> + Send + Sync + 'static,
@@ -93,19 +537,128 @@ pub trait DispatchEffect: Context {
         self.send_effect(effect);
     }
 
+    /// Dispatch `effect` as the root of a new causality chain tagged with `correlation_id`.
+    #[inline]
+    #[track_caller]
+    fn dispatch_correlated<F>(&self, correlation_id: CorrelationId, effect: F)
+    where
+        F: EffectFn<Self::Model> + Send + Sync + 'static,
+    {
+        let effect = Effect::new(effect)
+            .with_lineage(Lineage::of(self))
+            .with_correlation_id(Some(correlation_id));
+        self.effects_tx().send_or_drop(effect);
+    }
+
+    /// Dispatch an effect that is parked instead of run while `tag` is paused.
+    #[inline]
+    #[track_caller]
+    fn dispatch_tagged<F>(&self, tag: &'static str, effect: F)
+    where
+        F: EffectFn<Self::Model> + Send + Sync + 'static,
+    {
+        let effect = Effect::new(effect)
+            .with_lineage(Lineage::of(self))
+            .with_tag(tag);
+        self.effects_tx().send_or_drop(effect);
+    }
+
+    /// Dispatch an effect that is dropped unrun if the loop is overloaded when it is reached.
+    #[inline]
+    #[track_caller]
+    fn dispatch_sheddable<F>(&self, effect: F)
+    where
+        F: EffectFn<Self::Model> + Send + Sync + 'static,
+    {
+        let effect = Effect::new(effect)
+            .with_lineage(Lineage::of(self))
+            .sheddable();
+        self.effects_tx().send_or_drop(effect);
+    }
+
+    /// Dispatch an effect that waits for the current frame to end before it runs.
+    #[inline]
+    #[track_caller]
+    fn dispatch_next_frame<F>(&self, effect: F)
+    where
+        F: EffectFn<Self::Model> + Send + Sync + 'static,
+    {
+        let effect = Effect::new(effect)
+            .with_lineage(Lineage::of(self))
+            .next_frame();
+        self.effects_tx().send_or_drop(effect);
+    }
+
+    /// Dispatch an effect that merges with a still-queued effect of the same
+    /// type. The merged effect runs at the queue position of the first one.
+    #[inline]
+    #[track_caller]
+    fn dispatch_coalesced<E>(&self, effect: E)
+    where
+        E: CoalescingEffect<Self::Model>,
+    {
+        self.effects_tx().send_coalesced(effect);
+    }
+
     #[must_use]
     #[inline]
+    #[track_caller]
     fn dispatch_sync(&self, effect: impl EffectFn<Self::Model>) -> oneshot::Receiver<()> {
+        self.dispatch_with_result(effect)
+    }
+
+    /// Dispatch `effect` and receive its return value once it has run on the loop.
+    #[must_use]
+    #[inline]
+    #[track_caller]
+    fn dispatch_with_result<F, R>(&self, effect: F) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(&mut Syzygy<Self::Model>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
         let (tx, rx) = oneshot::channel();
         let wrapped_effect = move |ctx: &mut Syzygy<Self::Model>| {
-            (effect)(ctx);
-            let _ = tx.send(());
+            let _ = tx.send((effect)(ctx));
         };
         self.send_effect(wrapped_effect);
         rx
     }
 
+    /// Awaitable form of `dispatch_with_result`. Errors if the effect was dropped unrun.
     #[inline]
+    #[track_caller]
+    fn dispatch_await<F, R>(
+        &self,
+        effect: F,
+    ) -> impl Future<Output = Result<R, oneshot::error::RecvError>> + Send + 'static
+    where
+        F: FnOnce(&mut Syzygy<Self::Model>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        self.dispatch_with_result(effect)
+    }
+
+    /// Blocking form of `dispatch_with_result` for plain threads. Never call it from
+    /// the thread running the loop, the effect cannot run while it waits.
+    #[track_caller]
+    fn dispatch_blocking<F, R>(
+        &self,
+        effect: F,
+        timeout: Duration,
+    ) -> Result<R, crossbeam_channel::RecvTimeoutError>
+    where
+        F: FnOnce(&mut Syzygy<Self::Model>) -> R + Send + Sync + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.send_effect(move |ctx: &mut Syzygy<Self::Model>| {
+            let _ = tx.send((effect)(ctx));
+        });
+        rx.recv_timeout(timeout)
+    }
+
+    #[inline]
+    #[track_caller]
     fn dispatch_update<F>(&self, update: F)
     where
         F: FnOnce(&mut Self::Model) + Send + Sync + 'static,
@@ -117,29 +670,145 @@ pub trait DispatchEffect: Context {
     }
 
     #[inline]
+    #[track_caller]
     fn spawn<F>(&self, f: F)
     where
         F: FnOnce(AsyncContext<Self::Model>) + Send + Sync + 'static,
     {
+        let origin = Location::caller();
         let wrapped = move |syzygy: &mut Syzygy<Self::Model>| {
             let ctx = AsyncContext::from_context(syzygy);
-            tokio::task::spawn_blocking(move || f(ctx));
+            syzygy.spawner().spawn_blocking_at(origin, move || f(ctx));
         };
         self.dispatch(wrapped);
     }
 
     #[inline]
+    #[track_caller]
     fn task<F, Fut>(&self, f: F)
     where
         F: FnOnce(AsyncContext<Self::Model>) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
+        let origin = Location::caller();
         let wrapped = move |syzygy: &mut Syzygy<Self::Model>| {
             let ctx = AsyncContext::from_context(syzygy);
-            tokio::spawn(async move {
+            syzygy.spawner().spawn_at(origin, async move {
                 (f)(ctx).await;
             });
         };
         self.send_effect(wrapped);
     }
+
+    /// Like `task` for fallible futures. `on_ok` runs on the loop with the `Ok` value,
+    /// an `Err` goes to the `on_task_failed` handler for `E`, or is logged if there is none.
+    #[inline]
+    #[track_caller]
+    fn task_ok<F, Fut, T, E, K>(&self, f: F, on_ok: K)
+    where
+        F: FnOnce(AsyncContext<Self::Model>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + Sync + 'static,
+        E: fmt::Debug + Send + Sync + 'static,
+        K: FnOnce(&mut Syzygy<Self::Model>, T) + Send + Sync + 'static,
+    {
+        let origin = Location::caller();
+        self.task(move |ctx| async move {
+            let tx = ctx.effects_tx().clone();
+            let lineage = Lineage::of(&ctx);
+            let result = f(ctx).await;
+            let effect = Effect::new(move |syzygy: &mut Syzygy<Self::Model>| match result {
+                Ok(value) => on_ok(syzygy, value),
+                Err(error) => syzygy.task_failed(TaskFailed { error, origin }),
+            })
+            .with_origin(origin)
+            .with_lineage(lineage);
+            let _ = tx.send(effect);
+        });
+    }
+
+    /// Like `task`, but `on_result` only runs if `select` yields the same value after the
+    /// future as when it was spawned. Stale results are dropped.
+    #[inline]
+    #[track_caller]
+    fn task_if_unchanged<S, K, F, Fut, O, R>(&self, select: S, f: F, on_result: R)
+    where
+        S: Fn(&Self::Model) -> K + Send + Sync + 'static,
+        K: PartialEq + Send + Sync + 'static,
+        F: FnOnce(AsyncContext<Self::Model>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = O> + Send + 'static,
+        O: Send + Sync + 'static,
+        R: FnOnce(&mut Syzygy<Self::Model>, O) + Send + Sync + 'static,
+    {
+        let origin = Location::caller();
+        let spawn = move |syzygy: &mut Syzygy<Self::Model>| {
+            let before = select(&syzygy.model);
+            let ctx = AsyncContext::from_context(syzygy);
+            syzygy.spawner().spawn_at(origin, async move {
+                let tx = ctx.effects_tx().clone();
+                let lineage = Lineage::of(&ctx);
+                let output = f(ctx).await;
+                let effect = Effect::new(move |syzygy: &mut Syzygy<Self::Model>| {
+                    if select(&syzygy.model) == before {
+                        on_result(syzygy, output);
+                    } else {
+                        log::debug!("Dropping stale result of task spawned at {origin}");
+                    }
+                })
+                .with_origin(origin)
+                .with_lineage(lineage);
+                let _ = tx.send(effect);
+            });
+        };
+        self.send_effect(spawn);
+    }
+
+    /// Like `task`, but dispatches `on_timeout` if the future is still running after
+    /// `deadline`. The output is always handed to `on_result`, wrapped in `Late` if
+    /// it arrived after the timeout.
+    #[inline]
+    #[track_caller]
+    fn task_with_deadline<F, Fut, O, R, T>(
+        &self,
+        f: F,
+        deadline: Duration,
+        on_result: R,
+        on_timeout: T,
+    ) where
+        F: FnOnce(AsyncContext<Self::Model>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = O> + Send + 'static,
+        O: Send + Sync + 'static,
+        R: FnOnce(&mut Syzygy<Self::Model>, Deadline<O>) + Send + Sync + 'static,
+        T: EffectFn<Self::Model>,
+    {
+        let origin = Location::caller();
+        self.task(move |ctx| async move {
+            let tx = ctx.effects_tx().clone();
+            let lineage = Lineage::of(&ctx);
+            let sleep = ctx.spawner().sleep(deadline);
+            let mut fut = std::pin::pin!(f(ctx));
+            let on_time = tokio::select! {
+                biased;
+                output = &mut fut => Some(output),
+                () = sleep => None,
+            };
+            let output = if let Some(output) = on_time {
+                Deadline::OnTime(output)
+            } else {
+                let effect = Effect::new(on_timeout)
+                    .with_origin(origin)
+                    .with_lineage(lineage);
+                if matches!(tx.send(effect), Err(SendError::Disconnected(_))) {
+                    return;
+                }
+                Deadline::Late(fut.await)
+            };
+            let effect = Effect::new(move |syzygy: &mut Syzygy<Self::Model>| {
+                on_result(syzygy, output);
+            })
+            .with_origin(origin)
+            .with_lineage(lineage);
+            let _ = tx.send(effect);
+        });
+    }
 }
@@ -0,0 +1,356 @@
+use std::{
+    fmt,
+    future::Future,
+    panic::Location,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use rustc_hash::FxHashMap;
+use tokio::sync::Semaphore;
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, EffectsTx, Lineage},
+    model::Model,
+    runtime::RuntimeAccess,
+    scheduler::{Backoff, Schedule},
+    syzygy::Syzygy,
+};
+
+type OnStatus<M> = Arc<dyn Fn(&mut Syzygy<M>, JobId, JobStatus) + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Queued,
+    /// Running its `attempt`th try, counting from 1.
+    Running {
+        attempt: usize,
+    },
+    Failed,
+    Done,
+}
+
+impl JobStatus {
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Failed | Self::Done)
+    }
+}
+
+#[derive(Debug)]
+struct JobsInner {
+    permits: Semaphore,
+    next_id: AtomicU64,
+    statuses: Mutex<FxHashMap<JobId, JobStatus>>,
+}
+
+/// Async job queue running at most `max_concurrency` jobs at once on the spawner.
+/// Clones share the queue, so it can be kept as a resource.
+pub struct Jobs<M: Model> {
+    inner: Arc<JobsInner>,
+    on_status: Option<OnStatus<M>>,
+}
+
+impl<M: Model> Jobs<M> {
+    /// # Panics
+    /// Panics if `max_concurrency` is zero, since no job could ever start.
+    #[must_use]
+    pub fn new(max_concurrency: usize) -> Self {
+        assert!(
+            max_concurrency > 0,
+            "Jobs needs at least one concurrent slot"
+        );
+        Self {
+            inner: Arc::new(JobsInner {
+                permits: Semaphore::new(max_concurrency),
+                next_id: AtomicU64::new(1),
+                statuses: Mutex::default(),
+            }),
+            on_status: None,
+        }
+    }
+
+    /// Run `f` on the loop whenever a job changes status.
+    #[must_use]
+    pub fn on_status<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Syzygy<M>, JobId, JobStatus) + Send + Sync + 'static,
+    {
+        self.on_status = Some(Arc::new(f));
+        self
+    }
+
+    /// Queue `job` and run `on_done` on the loop with its result.
+    #[track_caller]
+    pub fn enqueue<C, F, Fut, T, E, D>(&self, cx: &C, job: F, on_done: D) -> JobId
+    where
+        C: DispatchEffect<Model = M> + RuntimeAccess,
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + Sync + 'static,
+        E: fmt::Display + Send + Sync + 'static,
+        D: FnOnce(&mut Syzygy<M>, Result<T, E>) + Send + Sync + 'static,
+    {
+        self.enqueue_with_retry(
+            cx,
+            0,
+            Backoff::new(Duration::default(), Duration::default()),
+            job,
+            on_done,
+        )
+    }
+
+    /// Like `enqueue`, but retries a failed job up to `retries` times, waiting `backoff`
+    /// between attempts. The job keeps its concurrency slot while it waits.
+    #[track_caller]
+    pub fn enqueue_with_retry<C, F, Fut, T, E, D>(
+        &self,
+        cx: &C,
+        retries: usize,
+        mut backoff: Backoff,
+        mut job: F,
+        on_done: D,
+    ) -> JobId
+    where
+        C: DispatchEffect<Model = M> + RuntimeAccess,
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + Sync + 'static,
+        E: fmt::Display + Send + Sync + 'static,
+        D: FnOnce(&mut Syzygy<M>, Result<T, E>) + Send + Sync + 'static,
+    {
+        let origin = Location::caller();
+        let id = JobId(self.inner.next_id.fetch_add(1, Ordering::Relaxed));
+        let jobs = self.clone();
+        let tx = cx.effects_tx().clone();
+        let lineage = Lineage::of(cx);
+        self.set_status(&tx, lineage, id, JobStatus::Queued);
+        let spawner = cx.spawner().clone();
+        cx.spawner().spawn(async move {
+            let Ok(_permit) = jobs.inner.permits.acquire().await else {
+                return;
+            };
+            let _guard = FailOnPanic {
+                jobs: &jobs,
+                tx: &tx,
+                lineage,
+                id,
+                origin,
+            };
+            let mut attempt = 1;
+            let result = loop {
+                jobs.set_status(&tx, lineage, id, JobStatus::Running { attempt });
+                match job().await {
+                    Err(error) if attempt <= retries => {
+                        log::warn!("Job {} from {origin} failed, retrying: {error}", id.0);
+                        let delay = backoff.next_delay().unwrap_or_default();
+                        spawner.sleep(delay).await;
+                        attempt += 1;
+                    }
+                    result => break result,
+                }
+            };
+            let status = if result.is_ok() {
+                JobStatus::Done
+            } else {
+                JobStatus::Failed
+            };
+            jobs.set_status(&tx, lineage, id, status);
+            let effect = Effect::new(move |syzygy: &mut Syzygy<M>| on_done(syzygy, result))
+                .with_origin(origin)
+                .with_lineage(lineage);
+            let _ = tx.send(effect);
+        });
+        id
+    }
+
+    #[must_use]
+    pub fn status(&self, id: JobId) -> Option<JobStatus> {
+        self.statuses().get(&id).copied()
+    }
+
+    /// Number of jobs that are queued or running.
+    #[must_use]
+    pub fn active(&self) -> usize {
+        self.statuses()
+            .values()
+            .filter(|status| !status.is_finished())
+            .count()
+    }
+
+    /// Forget the statuses of finished jobs.
+    pub fn clear_finished(&self) {
+        self.statuses().retain(|_, status| !status.is_finished());
+    }
+
+    fn set_status(&self, tx: &EffectsTx<M>, lineage: Lineage, id: JobId, status: JobStatus) {
+        self.statuses().insert(id, status);
+        if let Some(on_status) = &self.on_status {
+            let on_status = Arc::clone(on_status);
+            let effect = Effect::new(move |syzygy: &mut Syzygy<M>| on_status(syzygy, id, status));
+            let _ = tx.send(effect.with_lineage(lineage));
+        }
+    }
+
+    fn statuses(&self) -> std::sync::MutexGuard<'_, FxHashMap<JobId, JobStatus>> {
+        self.inner.statuses.lock().expect("Failed to acquire lock")
+    }
+}
+
+/// Marks the job `Failed` when its task unwinds, so a panicking job doesn't stay `Running`.
+struct FailOnPanic<'a, M: Model> {
+    jobs: &'a Jobs<M>,
+    tx: &'a EffectsTx<M>,
+    lineage: Lineage,
+    id: JobId,
+    origin: &'static Location<'static>,
+}
+
+impl<M: Model> Drop for FailOnPanic<'_, M> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            log::error!("Job {} from {} panicked", self.id.0, self.origin);
+            self.jobs
+                .set_status(self.tx, self.lineage, self.id, JobStatus::Failed);
+        }
+    }
+}
+
+impl<M: Model> Clone for Jobs<M> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            on_status: self.on_status.clone(),
+        }
+    }
+}
+
+impl<M: Model> fmt::Debug for Jobs<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Jobs")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(start_paused = true)]
+    async fn test_jobs() {
+        use std::sync::atomic::AtomicUsize;
+
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        let changes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let jobs = Jobs::new(1).on_status({
+            let changes = Arc::clone(&changes);
+            move |_, id, status| changes.lock().unwrap().push((id, status))
+        });
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        let blocker = jobs.enqueue(
+            &syzygy,
+            move || {
+                let mut rx = rx.clone();
+                async move {
+                    let _ = rx.wait_for(|done| *done).await;
+                    Ok::<_, String>(1)
+                }
+            },
+            |cx, result| cx.model_mut().counter += result.unwrap(),
+        );
+        let tries = Arc::new(AtomicUsize::new(0));
+        let flaky = jobs.enqueue_with_retry(
+            &syzygy,
+            2,
+            Backoff::new(Duration::from_millis(1), Duration::from_millis(1)),
+            {
+                let tries = Arc::clone(&tries);
+                move || {
+                    let attempt = tries.fetch_add(1, Ordering::SeqCst);
+                    async move { if attempt < 2 { Err("busy") } else { Ok(10) } }
+                }
+            },
+            |cx, result| cx.model_mut().counter += result.unwrap(),
+        );
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(matches!(
+            jobs.status(blocker),
+            Some(JobStatus::Running { attempt: 1 })
+        ));
+        assert_eq!(jobs.status(flaky), Some(JobStatus::Queued));
+        assert_eq!(jobs.active(), 2);
+
+        tx.send(true).unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 11);
+        assert_eq!(jobs.status(flaky), Some(JobStatus::Done));
+        assert_eq!(tries.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            *changes.lock().unwrap(),
+            [
+                (blocker, JobStatus::Queued),
+                (flaky, JobStatus::Queued),
+                (blocker, JobStatus::Running { attempt: 1 }),
+                (blocker, JobStatus::Done),
+                (flaky, JobStatus::Running { attempt: 1 }),
+                (flaky, JobStatus::Running { attempt: 2 }),
+                (flaky, JobStatus::Running { attempt: 3 }),
+                (flaky, JobStatus::Done),
+            ]
+        );
+        jobs.clear_finished();
+        assert_eq!(jobs.status(blocker), None);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(start_paused = true)]
+    async fn test_job_panic_fails() {
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        let changes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let jobs = Jobs::new(1).on_status({
+            let changes = Arc::clone(&changes);
+            move |_, id, status| changes.lock().unwrap().push((id, status))
+        });
+        let id = jobs.enqueue(
+            &syzygy,
+            || async {
+                panic!("job blew up");
+                #[allow(unreachable_code)]
+                Ok::<i32, String>(1)
+            },
+            |cx, result| cx.model_mut().counter += result.unwrap(),
+        );
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        syzygy.handle_effects();
+        assert_eq!(jobs.status(id), Some(JobStatus::Failed));
+        assert_eq!(jobs.active(), 0);
+        assert_eq!(syzygy.model().counter, 0);
+        assert_eq!(
+            *changes.lock().unwrap(),
+            [
+                (id, JobStatus::Queued),
+                (id, JobStatus::Running { attempt: 1 }),
+                (id, JobStatus::Failed),
+            ]
+        );
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    #[should_panic(expected = "at least one concurrent slot")]
+    fn test_jobs_zero_concurrency() {
+        let _ = Jobs::<TestModel>::new(0);
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    dispatch::{Effect, EffectsRx, QueueMemory},
+    model::Model,
+};
+
+struct Lane<M: Model> {
+    label: Option<&'static str>,
+    weight: usize,
+    queue: VecDeque<Effect<M>>,
+}
+
+/// Per-source queues drained by weighted round robin, see `SyzygyBuilder::lane_weight`.
+pub(crate) struct Lanes<M: Model> {
+    queues: Vec<Lane<M>>,
+    index: FxHashMap<Option<&'static str>, usize>,
+    current: usize,
+    served: usize,
+}
+
+impl<M: Model> Default for Lanes<M> {
+    fn default() -> Self {
+        Self {
+            queues: Vec::new(),
+            index: FxHashMap::default(),
+            current: 0,
+            served: 0,
+        }
+    }
+}
+
+impl<M: Model> std::fmt::Debug for Lanes<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.queues
+                    .iter()
+                    .map(|lane| (lane.label, lane.queue.len())),
+            )
+            .finish()
+    }
+}
+
+impl<M: Model> Lanes<M> {
+    pub(crate) fn set_weight(&mut self, label: Option<&'static str>, weight: usize) {
+        let lane = self.lane(label);
+        self.queues[lane].weight = weight.max(1);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queues.iter().map(|lane| lane.queue.len()).sum()
+    }
+
+    /// Sort everything waiting in the channel into its lane. Effects keep their bytes
+    /// counted against the memory cap until `pop` hands them out.
+    pub(crate) fn pull(&mut self, rx: &EffectsRx<M>) {
+        for effect in rx.try_iter() {
+            let lane = self.lane(effect.meta.lane);
+            self.queues[lane].queue.push_back(effect);
+        }
+    }
+
+    /// Next effect, taking up to `weight` effects in a row from each non-empty lane.
+    pub(crate) fn pop(&mut self, memory: &QueueMemory) -> Option<Effect<M>> {
+        for _ in 0..=self.queues.len() {
+            let lane = self.queues.get_mut(self.current)?;
+            if self.served < lane.weight
+                && let Some(effect) = lane.queue.pop_front()
+            {
+                self.served += 1;
+                memory.release(effect.meta.size);
+                return Some(effect);
+            }
+            self.current = (self.current + 1) % self.queues.len();
+            self.served = 0;
+        }
+        None
+    }
+
+    fn lane(&mut self, label: Option<&'static str>) -> usize {
+        *self.index.entry(label).or_insert_with(|| {
+            self.queues.push(Lane {
+                label,
+                weight: 1,
+                queue: VecDeque::new(),
+            });
+            self.queues.len() - 1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+    use std::sync::Arc;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_fair_lanes() {
+        use std::sync::Mutex;
+
+        let order = Arc::new(Mutex::new(String::new()));
+        let mut syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .lane_weight("ui", 2)
+            .build();
+        let net = syzygy.effects_tx().with_lane("net");
+        let ui = syzygy.effects_tx().with_lane("ui");
+        for (tx, name) in [
+            (&net, "n"),
+            (&net, "n"),
+            (&net, "n"),
+            (&net, "n"),
+            (&ui, "u"),
+            (&ui, "u"),
+            (&ui, "u"),
+        ] {
+            let order = Arc::clone(&order);
+            tx.send(Effect::new(move |_: &mut Syzygy<TestModel>| {
+                order.lock().unwrap().push_str(name);
+            }))
+            .unwrap();
+        }
+
+        let report = syzygy.handle_effects_max(5);
+        assert_eq!(report.pending, 2);
+        assert!(syzygy.queued_bytes() > 0);
+        syzygy.handle_effects();
+        assert_eq!(syzygy.queued_bytes(), 0);
+        assert_eq!(*order.lock().unwrap(), "uununnn");
+    }
+}
@@ -1,16 +1,42 @@
-#![feature(downcast_unchecked)]
 #![feature(min_specialization)]
+pub mod actor;
+pub mod broker;
+pub mod capability;
 pub mod context;
+#[cfg(feature = "devtools")]
+pub mod devtools;
 pub mod dispatch;
+pub mod jobs;
+mod lanes;
+pub mod local;
 pub mod model;
+pub mod pipeline;
+pub mod plugin;
 pub mod resource;
+pub mod resumable;
+pub mod runtime;
+pub mod scheduler;
+pub mod scope;
+pub mod signals;
+pub mod simulation;
+pub mod state_machine;
 pub mod syzygy;
+pub mod testing;
 
 pub mod prelude {
-    pub use crate::context::{Context, FromContext, IntoContext, r#async::AsyncContext};
+    pub use crate::broker::SendMessage;
+    pub use crate::context::{
+        Context, FromContext, IntoContext, r#async::AsyncContext, view::SyzygyView,
+    };
     pub use crate::dispatch::DispatchEffect;
     pub use crate::model::{ModelAccess, ModelModify};
+    pub use crate::plugin::Plugin;
     pub use crate::resource::{ResourceAccess, ResourceModify, Resources};
+    pub use crate::resumable::ResumeEffect;
+    pub use crate::runtime::{RuntimeAccess, RuntimeSpawner};
+    pub use crate::scheduler::{ReconnectEffect, ScheduleEffect, every};
+    pub use crate::scope::ScopeEffect;
+    pub use crate::signals::SignalEffect;
     #[cfg(feature = "parallel")]
     pub use crate::spawn::{RayonPool, SpawnParallel};
     pub use crate::syzygy::Syzygy;
@@ -0,0 +1,182 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::VecDeque,
+    fmt,
+    rc::Rc,
+};
+
+use rustc_hash::FxHashMap;
+
+pub type LocalEffect<M> = Box<dyn FnOnce(&mut LocalSyzygy<M>)>;
+
+/// Clonable handle for queueing effects from callbacks on the owning thread.
+pub struct LocalQueue<M: 'static>(Rc<RefCell<VecDeque<LocalEffect<M>>>>);
+
+impl<M> LocalQueue<M> {
+    pub fn dispatch<F>(&self, effect: F)
+    where
+        F: FnOnce(&mut LocalSyzygy<M>) + 'static,
+    {
+        self.0.borrow_mut().push_back(Box::new(effect));
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    fn pop(&self) -> Option<LocalEffect<M>> {
+        self.0.borrow_mut().pop_front()
+    }
+}
+
+impl<M> Clone for LocalQueue<M> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<M> Default for LocalQueue<M> {
+    fn default() -> Self {
+        Self(Rc::default())
+    }
+}
+
+impl<M> fmt::Debug for LocalQueue<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalQueue")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Single-threaded `Syzygy` without a runtime. Neither the model, the resources nor the
+/// effects need to be `Send`, and there is no task or thread spawning.
+pub struct LocalSyzygy<M: 'static> {
+    pub model: M,
+    resources: FxHashMap<TypeId, Box<dyn Any>>,
+    queue: LocalQueue<M>,
+}
+
+impl<M> LocalSyzygy<M> {
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            resources: FxHashMap::default(),
+            queue: LocalQueue::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    pub fn model_mut(&mut self) -> &mut M {
+        &mut self.model
+    }
+
+    pub fn query<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&M) -> R,
+    {
+        f(&self.model)
+    }
+
+    pub fn update<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut M),
+    {
+        f(&mut self.model);
+    }
+
+    /// Insert or replace `T`, returning the previous value.
+    pub fn insert_resource<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.resources
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .map(|previous| {
+                *previous
+                    .downcast::<T>()
+                    .expect("Resource type should match its TypeId")
+            })
+    }
+
+    #[must_use]
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        self.resources
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut::<T>())
+    }
+
+    pub fn dispatch<F>(&self, effect: F)
+    where
+        F: FnOnce(&mut LocalSyzygy<M>) + 'static,
+    {
+        self.queue.dispatch(effect);
+    }
+
+    #[must_use]
+    pub fn queue(&self) -> LocalQueue<M> {
+        self.queue.clone()
+    }
+
+    /// Run queued effects, including ones they dispatch, until the queue is empty.
+    pub fn handle_effects(&mut self) -> usize {
+        let mut processed = 0;
+        while let Some(effect) = self.queue.pop() {
+            effect(self);
+            processed += 1;
+        }
+        processed
+    }
+}
+
+impl<M: fmt::Debug> fmt::Debug for LocalSyzygy<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSyzygy")
+            .field("model", &self.model)
+            .field("queue", &self.queue)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_local_syzygy() {
+        use std::{cell::Cell, rc::Rc};
+
+        let clicks = Rc::new(Cell::new(0));
+        let mut syzygy = LocalSyzygy::new(Rc::clone(&clicks));
+        syzygy.insert_resource(Rc::new("label"));
+
+        let queue = syzygy.queue();
+        let on_click = move || {
+            queue.dispatch(|cx: &mut LocalSyzygy<Rc<Cell<i32>>>| {
+                cx.model().set(cx.model().get() + 1);
+                cx.dispatch(|cx| cx.update(|m| m.set(m.get() * 10)));
+            });
+        };
+        on_click();
+        on_click();
+
+        assert_eq!(syzygy.handle_effects(), 4);
+        assert_eq!(clicks.get(), 200);
+        assert_eq!(**syzygy.resource::<Rc<&str>>().unwrap(), "label");
+    }
+}
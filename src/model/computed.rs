@@ -0,0 +1,137 @@
+use std::{
+    any::Any,
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::syzygy::Syzygy;
+
+use super::Model;
+
+type ComputeFn<M> = Box<dyn Fn(&M) -> Arc<dyn Any + Send + Sync> + Send + Sync>;
+
+struct ComputedEntry<M> {
+    compute: ComputeFn<M>,
+    cache: Mutex<Option<(u64, Arc<dyn Any + Send + Sync>)>>,
+}
+
+/// Computed values registered on a `Syzygy`, keyed by name.
+pub struct ComputedValues<M>(FxHashMap<&'static str, ComputedEntry<M>>);
+
+impl<M> Default for ComputedValues<M> {
+    fn default() -> Self {
+        Self(FxHashMap::default())
+    }
+}
+
+impl<M> fmt::Debug for ComputedValues<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// Typed key returned by `Syzygy::computed`.
+pub struct Computed<T> {
+    name: &'static str,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> Computed<T> {
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T> Clone for Computed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Computed<T> {}
+
+impl<T> fmt::Debug for Computed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Computed").field(&self.name).finish()
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    /// Register `f` under `name`. Its result is cached until the model version changes.
+    pub fn computed<T, F>(&mut self, name: &'static str, f: F) -> Computed<T>
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&M) -> T + Send + Sync + 'static,
+    {
+        let entry = ComputedEntry {
+            compute: Box::new(move |model| Arc::new(f(model))),
+            cache: Mutex::new(None),
+        };
+        self.computed.0.insert(name, entry);
+        Computed {
+            name,
+            _value: PhantomData,
+        }
+    }
+
+    /// Cached value of `key`, recomputed first if the model changed since the last call.
+    pub fn get_computed<T>(&self, key: Computed<T>) -> Arc<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        let entry = self
+            .computed
+            .0
+            .get(key.name)
+            .expect("Computed value should be registered");
+        let mut cache = entry.cache.lock().expect("Failed to acquire lock");
+        let value = match &*cache {
+            Some((version, value)) if *version == self.model_version() => Arc::clone(value),
+            _ => {
+                let value = (entry.compute)(&self.model);
+                *cache = Some((self.model_version(), Arc::clone(&value)));
+                value
+            }
+        };
+        value
+            .downcast::<T>()
+            .expect("Computed value type should match its key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, increment},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_computed() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let runs_clone = Arc::clone(&runs);
+        let model = TestModel { counter: 2 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let doubled = syzygy.computed("doubled", move |m: &TestModel| {
+            runs_clone.fetch_add(1, Ordering::SeqCst);
+            m.counter * 2
+        });
+
+        assert_eq!(*syzygy.get_computed(doubled), 4);
+        assert_eq!(*syzygy.get_computed(doubled), 4);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        syzygy.dispatch(increment);
+        syzygy.handle_effects();
+        assert_eq!(*syzygy.get_computed(doubled), 6);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+
+use crate::syzygy::Syzygy;
+
+use super::Model;
+
+pub use syzygy_macros::DirtyTrack;
+
+/// Bitset of changed fields, stored in the `#[dirty]` field of a `DirtyTrack` model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dirty(u64);
+
+impl Dirty {
+    #[inline]
+    pub fn mark(&mut self, field: usize) {
+        self.0 |= 1 << field;
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    fn take(&mut self) -> u64 {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// Models whose fields record their own changes. Derive it with `#[derive(DirtyTrack)]`,
+/// which generates `set_<field>` and `<field>_mut` methods that mark the field dirty.
+pub trait DirtyTrack {
+    /// Names of the tracked fields, in bit order.
+    const FIELDS: &'static [&'static str];
+
+    fn dirty_mut(&mut self) -> &mut Dirty;
+}
+
+/// Fields changed since the last `Syzygy::take_dirty`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DirtyFields {
+    bits: u64,
+    names: &'static [&'static str],
+}
+
+impl DirtyFields {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    #[must_use]
+    pub fn contains(&self, field: &str) -> bool {
+        self.iter().any(|name| name == field)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> {
+        let bits = self.bits;
+        self.names
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| bits & (1 << i) != 0)
+            .map(|(_, name)| *name)
+    }
+}
+
+impl fmt::Debug for DirtyFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<M: Model + DirtyTrack> Syzygy<M> {
+    /// Return and clear the fields changed since the last call, e.g. after each batch.
+    pub fn take_dirty(&mut self) -> DirtyFields {
+        DirtyFields {
+            bits: self.model.dirty_mut().take(),
+            names: M::FIELDS,
+        }
+    }
+}
@@ -2,13 +2,31 @@ use core::fmt;
 
 use crate::context::Context;
 
+mod computed;
+mod dirty;
+mod read_handle;
 mod unsync;
+mod watched;
+
+pub use computed::{Computed, ComputedValues};
+pub use dirty::{Dirty, DirtyFields, DirtyTrack};
+pub use read_handle::ReadHandle;
+pub use watched::{WatchReceiver, Watched};
 
 pub trait Model: fmt::Debug + Send + Sync + 'static {
     type Snapshot: Clone + Send + Sync + 'static;
     fn to_snapshot(&self) -> Self::Snapshot;
 }
 
+/// Implement instead of `Model` for models that are never read through snapshots.
+/// Their snapshot is `()`, so spawning tasks does not copy the model.
+pub trait NoSnapshot: fmt::Debug + Send + Sync + 'static {}
+
+impl<T: NoSnapshot> Model for T {
+    type Snapshot = ();
+    fn to_snapshot(&self) -> Self::Snapshot {}
+}
+
 pub trait ModelAccess: Context {
     #[must_use]
     fn model(&self) -> &Self::Model;
@@ -0,0 +1,71 @@
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use super::Model;
+
+/// Clonable, thread-safe view of the snapshot last published by `Syzygy`.
+pub struct ReadHandle<M: Model> {
+    snapshot: Arc<RwLock<Arc<M::Snapshot>>>,
+}
+
+impl<M: Model> ReadHandle<M> {
+    pub(crate) fn new(snapshot: M::Snapshot) -> Self {
+        Self {
+            snapshot: Arc::new(RwLock::new(Arc::new(snapshot))),
+        }
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> Arc<M::Snapshot> {
+        let lock = self.snapshot.read().expect("Failed to acquire read lock");
+        Arc::clone(&lock)
+    }
+
+    pub(crate) fn publish(&self, snapshot: M::Snapshot) {
+        let snapshot = Arc::new(snapshot);
+        let mut lock = self.snapshot.write().expect("Failed to acquire write lock");
+        *lock = snapshot;
+    }
+}
+
+impl<M: Model> Clone for ReadHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            snapshot: Arc::clone(&self.snapshot),
+        }
+    }
+}
+
+impl<M: Model> fmt::Debug for ReadHandle<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadHandle").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, increment},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_read_handle() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        let handle = syzygy.read_handle();
+        assert_eq!(handle.latest().counter, 0);
+
+        syzygy.dispatch(increment);
+        syzygy.dispatch(increment);
+        assert_eq!(handle.latest().counter, 0);
+
+        syzygy.handle_effects();
+        let reader = std::thread::spawn(move || handle.latest().counter);
+        assert_eq!(reader.join().unwrap(), 2);
+    }
+}
@@ -0,0 +1,157 @@
+use std::{fmt, ops::Deref};
+
+use tokio::sync::watch;
+
+use crate::syzygy::Syzygy;
+
+use super::Model;
+
+/// Model field that notifies `WatchReceiver`s from `Syzygy::watch` on every write.
+/// Clones share their receivers, so snapshots of the model do not lose watchers.
+pub struct Watched<T> {
+    value: T,
+    version: u64,
+    tx: watch::Sender<T>,
+}
+
+impl<T: Clone> Watched<T> {
+    pub fn new(value: T) -> Self {
+        let (tx, _) = watch::channel(value.clone());
+        Self {
+            value,
+            version: 0,
+            tx,
+        }
+    }
+
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.notify();
+    }
+
+    pub fn modify<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let result = f(&mut self.value);
+        self.notify();
+        result
+    }
+
+    /// Number of writes so far.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    #[must_use]
+    pub fn subscribe(&self) -> WatchReceiver<T> {
+        WatchReceiver(self.tx.subscribe())
+    }
+
+    fn notify(&mut self) {
+        self.version += 1;
+        self.tx.send_replace(self.value.clone());
+    }
+}
+
+impl<T> Deref for Watched<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone> Clone for Watched<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            version: self.version,
+            tx: self.tx.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Default> Default for Watched<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Watched<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watched")
+            .field("value", &self.value)
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Receiving side of a `Watched` field.
+#[derive(Debug)]
+pub struct WatchReceiver<T>(watch::Receiver<T>);
+
+impl<T: Clone> WatchReceiver<T> {
+    /// Wait for the next write and return the new value. Writes made while the
+    /// receiver was not waiting are merged into one. `None` once the field is dropped.
+    pub async fn changed(&mut self) -> Option<T> {
+        self.0.changed().await.ok()?;
+        Some(self.0.borrow_and_update().clone())
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> T {
+        self.0.borrow().clone()
+    }
+}
+
+impl<T> Clone for WatchReceiver<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    /// Subscribe to a `Watched` field of the model.
+    pub fn watch<T, F>(&self, select: F) -> WatchReceiver<T>
+    where
+        T: Clone,
+        F: FnOnce(&M) -> &Watched<T>,
+    {
+        select(&self.model).subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_watched_field() {
+        #[derive(Debug, Clone, Default)]
+        struct WatchModel {
+            status: Watched<String>,
+            other: i32,
+        }
+
+        impl Model for WatchModel {
+            type Snapshot = Self;
+            fn to_snapshot(&self) -> Self::Snapshot {
+                self.clone()
+            }
+        }
+
+        let mut syzygy = Syzygy::builder().model(WatchModel::default()).build();
+        let mut status = syzygy.watch(|m| &m.status);
+        let waiter = tokio::spawn(async move { status.changed().await });
+
+        syzygy.update(|m| m.other += 1);
+        syzygy.update(|m| m.status.set("online".to_string()));
+        assert_eq!(waiter.await.unwrap().as_deref(), Some("online"));
+        assert_eq!(syzygy.model().status.version(), 1);
+        assert_eq!(syzygy.model().status.len(), 6);
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    any::Any,
+    fmt,
+    future::Future,
+    marker::PhantomData,
+    panic::Location,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, EffectsTx, Lineage},
+    model::Model,
+    runtime::{RuntimeAccess, Spawner},
+    scheduler::{Backoff, Schedule},
+    syzygy::Syzygy,
+};
+
+type Value = Box<dyn Any + Send + Sync>;
+type StageFuture<E> = Pin<Box<dyn Future<Output = Result<Value, E>> + Send>>;
+type StageFn<E> = Arc<dyn Fn(&(dyn Any + Send + Sync)) -> StageFuture<E> + Send + Sync>;
+type OnStage<M> = Arc<dyn Fn(&mut Syzygy<M>, &StageReport) + Send + Sync>;
+
+struct Stage<E> {
+    name: &'static str,
+    retries: usize,
+    backoff: Backoff,
+    run: StageFn<E>,
+}
+
+/// Timing of one finished stage, successful or not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageReport {
+    pub pipeline: &'static str,
+    pub stage: &'static str,
+    pub attempts: usize,
+    pub elapsed: Duration,
+    pub ok: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("stage `{stage}` of pipeline `{pipeline}` failed after {attempts} attempts: {error}")]
+pub struct PipelineError<E> {
+    pub pipeline: &'static str,
+    pub stage: &'static str,
+    pub attempts: usize,
+    pub error: E,
+}
+
+/// Named chain of async stages, each fed the previous stage's output. Stages are
+/// retried on failure up to their `retries` and timed; see `StageReport`.
+pub struct Pipeline<M: Model, I, O, E> {
+    name: &'static str,
+    stages: Vec<Stage<E>>,
+    on_stage: Option<OnStage<M>>,
+    _io: PhantomData<fn(I) -> O>,
+}
+
+impl<M, T, E> Pipeline<M, T, T, E>
+where
+    M: Model,
+    T: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            stages: Vec::new(),
+            on_stage: None,
+            _io: PhantomData,
+        }
+    }
+}
+
+impl<M, I, O, E> Pipeline<M, I, O, E>
+where
+    M: Model,
+    I: Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    #[must_use]
+    pub fn stage<F, Fut, O2>(mut self, name: &'static str, f: F) -> Pipeline<M, I, O2, E>
+    where
+        F: Fn(O) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O2, E>> + Send + 'static,
+        O2: Send + Sync + 'static,
+    {
+        let run: StageFn<E> = Arc::new(move |input| {
+            let input = input
+                .downcast_ref::<O>()
+                .expect("Stage input type should match the previous stage")
+                .clone();
+            let future = f(input);
+            Box::pin(async move { future.await.map(|output| Box::new(output) as Value) })
+        });
+        self.stages.push(Stage {
+            name,
+            retries: 0,
+            backoff: Backoff::new(Duration::default(), Duration::default()),
+            run,
+        });
+        Pipeline {
+            name: self.name,
+            stages: self.stages,
+            on_stage: self.on_stage,
+            _io: PhantomData,
+        }
+    }
+}
+
+impl<M, I, O, E> Pipeline<M, I, O, E>
+where
+    M: Model,
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    /// Retry the last added stage up to `retries` more times when it fails, waiting
+    /// `backoff` between attempts.
+    #[must_use]
+    pub fn retries(mut self, retries: usize, backoff: Backoff) -> Self {
+        if let Some(stage) = self.stages.last_mut() {
+            stage.retries = retries;
+            stage.backoff = backoff;
+        }
+        self
+    }
+
+    /// Run `f` on the loop as each stage finishes.
+    #[must_use]
+    pub fn on_stage<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Syzygy<M>, &StageReport) + Send + Sync + 'static,
+    {
+        self.on_stage = Some(Arc::new(f));
+        self
+    }
+
+    /// Run the stages on a task and hand the result and stage timings to `on_done`
+    /// on the loop. A failing stage ends the pipeline once its retries are used up.
+    #[track_caller]
+    pub fn run<C, F>(self, cx: &C, input: I, on_done: F)
+    where
+        C: DispatchEffect<Model = M> + RuntimeAccess,
+        F: FnOnce(&mut Syzygy<M>, Result<O, PipelineError<E>>, Vec<StageReport>)
+            + Send
+            + Sync
+            + 'static,
+    {
+        let origin = Location::caller();
+        let tx = cx.effects_tx().clone();
+        let lineage = Lineage::of(cx);
+        let spawner = cx.spawner().clone();
+        cx.spawner().spawn(async move {
+            let mut reports = Vec::with_capacity(self.stages.len());
+            let result = self
+                .run_stages(
+                    Box::new(input),
+                    &tx,
+                    &spawner,
+                    lineage,
+                    origin,
+                    &mut reports,
+                )
+                .await;
+            let result = result.map(|output| {
+                *output
+                    .downcast::<O>()
+                    .expect("Pipeline output type should match the last stage")
+            });
+            let effect = Effect::new(move |syzygy: &mut Syzygy<M>| {
+                on_done(syzygy, result, reports);
+            })
+            .with_origin(origin)
+            .with_lineage(lineage);
+            let _ = tx.send(effect);
+        });
+    }
+
+    async fn run_stages(
+        &self,
+        mut value: Value,
+        tx: &EffectsTx<M>,
+        spawner: &Spawner,
+        lineage: Lineage,
+        origin: &'static Location<'static>,
+        reports: &mut Vec<StageReport>,
+    ) -> Result<Value, PipelineError<E>> {
+        for stage in &self.stages {
+            let started = Instant::now();
+            let mut backoff = stage.backoff;
+            let mut attempts = 0;
+            let result = loop {
+                attempts += 1;
+                match (stage.run)(&*value).await {
+                    Err(_) if attempts <= stage.retries => {
+                        spawner
+                            .sleep(backoff.next_delay().unwrap_or_default())
+                            .await;
+                    }
+                    result => break result,
+                }
+            };
+            let report = StageReport {
+                pipeline: self.name,
+                stage: stage.name,
+                attempts,
+                elapsed: started.elapsed(),
+                ok: result.is_ok(),
+            };
+            if let Some(on_stage) = &self.on_stage {
+                let on_stage = Arc::clone(on_stage);
+                let report = report.clone();
+                let effect = Effect::new(move |syzygy: &mut Syzygy<M>| {
+                    on_stage(syzygy, &report);
+                })
+                .with_origin(origin)
+                .with_lineage(lineage);
+                let _ = tx.send(effect);
+            }
+            reports.push(report);
+            value = result.map_err(|error| PipelineError {
+                pipeline: self.name,
+                stage: stage.name,
+                attempts,
+                error,
+            })?;
+        }
+        Ok(value)
+    }
+}
+
+impl<M: Model, I, O, E> fmt::Debug for Pipeline<M, I, O, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("name", &self.name)
+            .field(
+                "stages",
+                &self.stages.iter().map(|s| s.name).collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+    use tokio::sync::oneshot;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_pipeline() {
+        use std::sync::{
+            Mutex,
+            atomic::{AtomicUsize, Ordering},
+        };
+
+        let origins = Arc::new(Mutex::new(Vec::new()));
+        let mut syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .audit_log({
+                let origins = Arc::clone(&origins);
+                move |meta| origins.lock().unwrap().push(meta.origin.line())
+            })
+            .build();
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = oneshot::channel();
+        let pipeline = Pipeline::new("import")
+            .stage("fetch", {
+                let fetches = Arc::clone(&fetches);
+                move |url: &'static str| {
+                    let attempt = fetches.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        if attempt == 0 {
+                            Err("timeout".to_string())
+                        } else {
+                            Ok(format!("{url}:1,2,3"))
+                        }
+                    }
+                }
+            })
+            .retries(
+                2,
+                Backoff::new(Duration::from_millis(5), Duration::from_millis(5)),
+            )
+            .stage("parse", |body: String| async move {
+                body.rsplit(':')
+                    .next()
+                    .unwrap()
+                    .split(',')
+                    .map(|n| n.parse::<i32>().map_err(|e| e.to_string()))
+                    .sum::<Result<i32, _>>()
+            })
+            .on_stage(|syzygy: &mut Syzygy<TestModel>, report| {
+                assert!(report.ok);
+                syzygy.model_mut().counter += 100;
+            });
+        let run_line = line!() + 1;
+        pipeline.run(&syzygy, "db", move |syzygy, result, reports| {
+            syzygy.model_mut().counter += result.unwrap();
+            tx.send(reports).unwrap();
+        });
+
+        let reports = loop {
+            syzygy.handle_effects();
+            tokio::task::yield_now().await;
+            if syzygy.model().counter >= 206 {
+                break rx.await.unwrap();
+            }
+        };
+        assert_eq!(syzygy.model().counter, 206);
+        assert_eq!(reports.len(), 2);
+        assert_eq!((reports[0].stage, reports[0].attempts), ("fetch", 2));
+        assert!(reports[0].elapsed >= Duration::from_millis(5));
+        assert_eq!(*origins.lock().unwrap(), [run_line; 3]);
+        assert_eq!((reports[1].stage, reports[1].attempts), ("parse", 1));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    model::Model,
+    syzygy::{SyzygyBuilder, syzygy_builder},
+};
+
+/// Reusable unit of setup (resources, startup effects) applied to a builder.
+pub trait Plugin<M: Model> {
+    fn build<S: syzygy_builder::State>(&self, builder: SyzygyBuilder<M, S>) -> SyzygyBuilder<M, S>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, TestResource, increment},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_plugin() {
+        struct TestPlugin;
+
+        impl Plugin<TestModel> for TestPlugin {
+            fn build<S: syzygy_builder::State>(
+                &self,
+                builder: SyzygyBuilder<TestModel, S>,
+            ) -> SyzygyBuilder<TestModel, S> {
+                builder
+                    .resource(TestResource {
+                        name: "plugin".to_string(),
+                    })
+                    .effect(increment)
+            }
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy = Syzygy::builder().plugin(TestPlugin).model(model).build();
+
+        assert_eq!(syzygy.resource::<TestResource>().name, "plugin");
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// Shared, lockable resource for state that is not cheap to clone or must be mutated in place.
+/// Debug builds panic when a thread locks a cell it already holds in a conflicting mode.
+pub struct ResourceCell<T> {
+    inner: Arc<RwLock<T>>,
+}
+
+impl<T> ResourceCell<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(value)),
+        }
+    }
+
+    #[must_use]
+    pub fn read(&self) -> ResourceRef<'_, T> {
+        let held = held::acquire(self.id(), false);
+        ResourceRef {
+            guard: self.inner.read().expect("Failed to acquire read lock"),
+            _held: held,
+        }
+    }
+
+    #[must_use]
+    pub fn write(&self) -> ResourceMut<'_, T> {
+        let held = held::acquire(self.id(), true);
+        ResourceMut {
+            guard: self.inner.write().expect("Failed to acquire write lock"),
+            _held: held,
+        }
+    }
+
+    fn id(&self) -> usize {
+        Arc::as_ptr(&self.inner).addr()
+    }
+}
+
+impl<T> Clone for ResourceCell<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: Default> Default for ResourceCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> fmt::Debug for ResourceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceCell").finish_non_exhaustive()
+    }
+}
+
+pub struct ResourceRef<'a, T> {
+    guard: RwLockReadGuard<'a, T>,
+    _held: held::Held,
+}
+
+impl<T> Deref for ResourceRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+pub struct ResourceMut<'a, T> {
+    guard: RwLockWriteGuard<'a, T>,
+    _held: held::Held,
+}
+
+impl<T> Deref for ResourceMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for ResourceMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+#[cfg(debug_assertions)]
+mod held {
+    use std::cell::RefCell;
+
+    thread_local! {
+        static HELD: RefCell<Vec<(usize, bool)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Record of a cell lock held by the current thread.
+    pub struct Held(usize);
+
+    pub fn acquire(id: usize, write: bool) -> Held {
+        HELD.with_borrow_mut(|held| {
+            if held
+                .iter()
+                .any(|&(held_id, held_write)| held_id == id && (write || held_write))
+            {
+                panic!("ResourceCell locked again on the same thread, this would deadlock");
+            }
+            held.push((id, write));
+        });
+        Held(id)
+    }
+
+    impl Drop for Held {
+        fn drop(&mut self) {
+            HELD.with_borrow_mut(|held| {
+                if let Some(pos) = held.iter().rposition(|&(id, _)| id == self.0) {
+                    held.swap_remove(pos);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(not(debug_assertions))]
+mod held {
+    pub struct Held;
+
+    pub fn acquire(_id: usize, _write: bool) -> Held {
+        Held
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_resource_cell() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy = Syzygy::builder()
+            .model(model)
+            .resource(ResourceCell::new(vec![1, 2]))
+            .build();
+
+        syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
+            cx.resource::<ResourceCell<Vec<i32>>>().write().push(3);
+        });
+        syzygy.handle_effects();
+
+        let cell = syzygy.resource::<ResourceCell<Vec<i32>>>();
+        let first = cell.read();
+        let second = cell.read();
+        assert_eq!(*first, [1, 2, 3]);
+        assert_eq!(second.len(), 3);
+    }
+
+    #[cfg(all(debug_assertions, not(feature = "parallel")))]
+    #[test]
+    #[should_panic(expected = "would deadlock")]
+    fn test_resource_cell_reentrant_write() {
+        let cell = crate::resource::ResourceCell::new(0);
+        let _read = cell.read();
+        let _write = cell.write();
+    }
+}
@@ -0,0 +1,13 @@
+use super::Resources;
+
+pub use syzygy_macros::ResourceGroup;
+
+/// Several resources registered together, see `SyzygyBuilder::resource_group`. Derive
+/// it with `#[derive(ResourceGroup)]`. Each field needs a distinct type, a later field
+/// of the same type replaces an earlier one.
+pub trait ResourceGroup: Sized {
+    fn register(self, resources: &mut Resources);
+
+    /// Collect the group back from `resources`, `None` if any member is missing.
+    fn from_resources(resources: &Resources) -> Option<Self>;
+}
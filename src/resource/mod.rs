@@ -0,0 +1,404 @@
+use std::{
+    any::{Any, TypeId},
+    ops::Deref,
+    sync::{Arc, RwLock, RwLockWriteGuard},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::{context::Context, syzygy::defer};
+
+mod cell;
+mod group;
+pub(crate) mod provide;
+mod random;
+
+pub use cell::{ResourceCell, ResourceMut, ResourceRef};
+pub use group::ResourceGroup;
+pub use provide::Provider;
+pub use random::{Random, RandomAccess};
+
+type ResourceMap = FxHashMap<TypeId, Box<dyn Any + Send + Sync>>;
+type ResourceListener<T> = Arc<dyn Fn(Option<&Arc<T>>, &Arc<T>) + Send + Sync>;
+
+#[derive(Default, Debug, Clone)]
+pub struct Resources {
+    map: Arc<RwLock<ResourceMap>>,
+    listeners: Arc<RwLock<ResourceMap>>,
+    read_only: bool,
+}
+
+impl Deref for Resources {
+    type Target = RwLock<ResourceMap>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+/// Passed to resource listeners when a resource is inserted or replaced.
+#[derive(Debug, Clone)]
+pub struct ResourceChanged<T> {
+    pub previous: Option<T>,
+    pub current: T,
+}
+
+impl Resources {
+    /// Handle that reads the same resources but panics on writes and has listeners of
+    /// its own. Used by `Syzygy::simulate`.
+    pub(crate) fn read_only(&self) -> Self {
+        Self {
+            map: Arc::clone(&self.map),
+            listeners: Arc::default(),
+            read_only: true,
+        }
+    }
+
+    pub(crate) fn write_map(&self) -> RwLockWriteGuard<'_, ResourceMap> {
+        assert!(
+            !self.read_only,
+            "Resources can't be written inside a simulation"
+        );
+        self.write().expect("Failed to acquire write lock")
+    }
+
+    pub fn insert<T>(&mut self, value: T)
+    where
+        T: Send + Sync + Clone + 'static,
+    {
+        let ty = TypeId::of::<T>();
+        let boxed_value = Box::new(Arc::new(value));
+        let mut lock = self.write_map();
+        lock.insert(ty, boxed_value);
+    }
+
+    #[must_use]
+    pub fn get<T>(&self) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let ty = TypeId::of::<T>();
+        let lock = self.read().expect("Failed to acquire read lock");
+        lock.get(&ty).map(|boxed_value| {
+            T::clone(
+                boxed_value
+                    .downcast_ref::<Arc<T>>()
+                    .expect("Resource type should match its TypeId"),
+            )
+        })
+    }
+
+    /// Like `get`, but only bumps the refcount instead of cloning `T`.
+    #[must_use]
+    pub fn get_arc<T>(&self) -> Option<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        let ty = TypeId::of::<T>();
+        let lock = self.read().expect("Failed to acquire read lock");
+        lock.get(&ty).map(|boxed_value| {
+            Arc::clone(
+                boxed_value
+                    .downcast_ref::<Arc<T>>()
+                    .expect("Resource type should match its TypeId"),
+            )
+        })
+    }
+
+    /// Insert a resource that is shared through its `Arc`, so `T` need not be `Clone`.
+    /// Read it back with `get_arc`. Notifies listeners like `replace`.
+    pub fn insert_arc<T>(&self, value: Arc<T>) -> Option<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        let current = Arc::clone(&value);
+        let previous = self.swap(value);
+        self.notify(previous.as_ref(), &current);
+        previous
+    }
+
+    /// Insert or replace `T`, notify its listeners and return the previous value.
+    pub fn replace<T>(&self, value: T) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let current = Arc::new(value);
+        let previous = self.swap(Arc::clone(&current));
+        self.notify(previous.as_ref(), &current);
+        previous.map(Arc::unwrap_or_clone)
+    }
+
+    fn swap<T>(&self, value: Arc<T>) -> Option<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.write_map()
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .map(|boxed_value| {
+                *boxed_value
+                    .downcast::<Arc<T>>()
+                    .expect("Resource type should match its TypeId")
+            })
+    }
+
+    /// Values are only cloned for listeners, so writes to unwatched resources stay cheap.
+    fn notify<T>(&self, previous: Option<&Arc<T>>, current: &Arc<T>)
+    where
+        T: Send + Sync + 'static,
+    {
+        for listener in self.listeners::<T>() {
+            listener(previous, current);
+        }
+    }
+
+    /// Overwrite `T` in place, or insert it, without notifying listeners. For values the
+    /// loop refreshes on every pass.
+    pub(crate) fn overwrite<T>(&self, value: T)
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let ty = TypeId::of::<T>();
+        let mut lock = self.write_map();
+        match lock
+            .get_mut(&ty)
+            .and_then(|boxed_value| boxed_value.downcast_mut::<Arc<T>>())
+        {
+            Some(current) => *Arc::make_mut(current) = value,
+            None => {
+                lock.insert(ty, Box::new(Arc::new(value)));
+            }
+        }
+    }
+
+    /// Return `T`, inserting `f()` under the same write lock if it is missing. Listeners
+    /// are not notified.
+    pub(crate) fn get_or_insert_with<T, F>(&self, f: F) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        if let Some(value) = self.get::<T>() {
+            return value;
+        }
+        let mut lock = self.write_map();
+        T::clone(
+            lock.entry(TypeId::of::<T>())
+                .or_insert_with(|| Box::new(Arc::new(f())))
+                .downcast_ref::<Arc<T>>()
+                .expect("Resource type should match its TypeId"),
+        )
+    }
+
+    /// Call `f` whenever `T` is inserted or replaced through `replace` or `insert_arc`.
+    pub fn subscribe<T, F>(&self, f: F)
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(&ResourceChanged<T>) + Send + Sync + 'static,
+    {
+        let listener: ResourceListener<T> = Arc::new(move |previous, current| {
+            f(&ResourceChanged {
+                previous: previous.map(|previous| T::clone(previous)),
+                current: T::clone(current),
+            });
+        });
+        let mut lock = self
+            .listeners
+            .write()
+            .expect("Failed to acquire write lock");
+        lock.entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<ResourceListener<T>>::new()))
+            .downcast_mut::<Vec<ResourceListener<T>>>()
+            .expect("Listener type should match its TypeId")
+            .push(listener);
+    }
+
+    fn listeners<T>(&self) -> Vec<ResourceListener<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        let lock = self.listeners.read().expect("Failed to acquire read lock");
+        lock.get(&TypeId::of::<T>())
+            .and_then(|listeners| listeners.downcast_ref::<Vec<ResourceListener<T>>>())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+pub trait ResourceAccess: Context {
+    fn resources(&self) -> &Resources;
+    fn resource<T>(&self) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.resources().get::<T>().unwrap()
+    }
+    fn try_resource<T>(&self) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.resources().get::<T>()
+    }
+    /// Shared handle to the `T` resource, cheaper than `resource` for large values.
+    fn resource_cached<T>(&self) -> Arc<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.resources().get_arc::<T>().unwrap()
+    }
+    fn with_resource<T, F, R>(&self, f: F) -> R
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(&T) -> R,
+    {
+        f(&self.resource::<T>())
+    }
+}
+
+pub trait ResourceModify: ResourceAccess {
+    fn add_resource<T>(&self, value: T)
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.resources().replace(value);
+    }
+
+    fn insert_resource<T>(&self, value: T) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.resources().replace(value)
+    }
+
+    fn insert_arc<T>(&self, value: Arc<T>) -> Option<Arc<T>>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.resources().insert_arc(value)
+    }
+
+    fn remove_resource<T>(&self) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let removed = self.resources().write_map().remove(&TypeId::of::<T>())?;
+        let removed = removed
+            .downcast::<Arc<T>>()
+            .expect("Resource type should match its TypeId");
+        Some(Arc::unwrap_or_clone(*removed))
+    }
+
+    /// Shadow the `T` resource with `value` while `f` runs, restoring the original afterwards.
+    fn with_resource_override<T, F, R>(&mut self, value: T, f: F) -> R
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(&mut Self) -> R,
+    {
+        let ty = TypeId::of::<T>();
+        let previous = self
+            .resources()
+            .write_map()
+            .insert(ty, Box::new(Arc::new(value)));
+        let resources = self.resources().clone();
+        let _restore = defer(move || {
+            let mut lock = resources.write_map();
+            match previous {
+                Some(previous) => lock.insert(ty, previous),
+                None => lock.remove(&ty),
+            };
+        });
+        f(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, TestResource},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_resource_override() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy = Syzygy::builder()
+            .model(model)
+            .resource(TestResource {
+                name: "original".to_string(),
+            })
+            .build();
+
+        let stub = TestResource {
+            name: "stub".to_string(),
+        };
+        let name = syzygy.with_resource_override(stub, |cx| cx.resource::<TestResource>().name);
+        assert_eq!(name, "stub");
+        assert_eq!(syzygy.resource::<TestResource>().name, "original");
+
+        syzygy.with_resource_override(7_u32, |cx| assert_eq!(cx.resource::<u32>(), 7));
+        assert!(syzygy.try_resource::<u32>().is_none());
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_resource_changed() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.on_resource_changed(|cx, change: &ResourceChanged<i32>| {
+            cx.model_mut().counter += change.current - change.previous.unwrap_or_default();
+        });
+
+        assert_eq!(syzygy.insert_resource(5_i32), None);
+        assert_eq!(syzygy.insert_resource(8_i32), Some(5));
+        assert_eq!(syzygy.insert_arc(Arc::new(10_i32)).as_deref(), Some(&8));
+        syzygy.add_resource(TestResource {
+            name: "unwatched".to_string(),
+        });
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 10);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_resource_cached() {
+        let model = TestModel { counter: 0 };
+        let test_resource = TestResource {
+            name: "test_str".to_string(),
+        };
+        let syzygy = Syzygy::builder()
+            .model(model)
+            .resource(test_resource)
+            .build();
+
+        let cached = syzygy.resource_cached::<TestResource>();
+        assert!(Arc::ptr_eq(
+            &cached,
+            &syzygy.resource_cached::<TestResource>()
+        ));
+        assert_eq!(cached.name, "test_str");
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_remove_resource() {
+        let model = TestModel { counter: 0 };
+        let test_resource = TestResource {
+            name: "test_str".to_string(),
+        };
+        let syzygy = Syzygy::builder()
+            .model(model)
+            .resource(test_resource)
+            .build();
+
+        let removed = syzygy.remove_resource::<TestResource>();
+        assert_eq!(
+            removed.map(|resource| resource.name).as_deref(),
+            Some("test_str")
+        );
+        assert!(syzygy.try_resource::<TestResource>().is_none());
+        assert!(syzygy.remove_resource::<TestResource>().is_none());
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    any::{TypeId, type_name},
+    fmt,
+};
+
+use super::Resources;
+
+type ResourceKey = (TypeId, &'static str);
+
+/// Constructor for a resource built from other resources, see `SyzygyBuilder::provide`.
+pub trait Provider<Args>: Send + Sync + 'static {
+    type Output: Clone + Send + Sync + 'static;
+
+    fn dependencies() -> Vec<(TypeId, &'static str)>;
+
+    /// Build the output, or `None` while a dependency is still missing.
+    fn provide(&self, resources: &Resources) -> Option<Self::Output>;
+}
+
+macro_rules! impl_provider {
+    ($($dep:ident),*) => {
+        impl<F, T, $($dep),*> Provider<($($dep,)*)> for F
+        where
+            F: Fn($(&$dep),*) -> T + Send + Sync + 'static,
+            T: Clone + Send + Sync + 'static,
+            $($dep: Send + Sync + 'static,)*
+        {
+            type Output = T;
+
+            fn dependencies() -> Vec<(TypeId, &'static str)> {
+                vec![$((TypeId::of::<$dep>(), type_name::<$dep>())),*]
+            }
+
+            #[allow(non_snake_case)]
+            fn provide(&self, resources: &Resources) -> Option<T> {
+                $(let $dep = resources.get_arc::<$dep>()?;)*
+                Some(self($(&$dep),*))
+            }
+        }
+    };
+}
+
+impl_provider!(A);
+impl_provider!(A, B);
+impl_provider!(A, B, C);
+impl_provider!(A, B, C, D);
+impl_provider!(A, B, C, D, E);
+impl_provider!(A, B, C, D, E, G);
+
+pub(crate) struct PendingProvider {
+    pub(crate) output: ResourceKey,
+    pub(crate) dependencies: Vec<ResourceKey>,
+    run: Box<dyn Fn(&Resources) -> bool + Send + Sync>,
+}
+
+impl PendingProvider {
+    pub(crate) fn new<P, Args>(provider: P) -> Self
+    where
+        P: Provider<Args>,
+    {
+        Self {
+            output: (TypeId::of::<P::Output>(), type_name::<P::Output>()),
+            dependencies: P::dependencies(),
+            run: Box::new(move |resources| {
+                provider
+                    .provide(resources)
+                    .map(|value| resources.replace(value))
+                    .is_some()
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for PendingProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingProvider")
+            .field("output", &self.output.1)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Run every provider whose dependencies are available, repeating until none makes progress.
+/// Providers of an already registered resource are dropped, explicit resources win.
+pub(crate) fn resolve(providers: &mut Vec<PendingProvider>, resources: &Resources) {
+    loop {
+        let before = providers.len();
+        providers.retain(|provider| {
+            let registered = resources
+                .read()
+                .expect("Failed to acquire read lock")
+                .contains_key(&provider.output.0);
+            if registered {
+                log::debug!(
+                    "Resource `{}` is already registered, skipping its provider",
+                    provider.output.1
+                );
+            }
+            !registered && !(provider.run)(resources)
+        });
+        if providers.len() == before {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::*,
+        syzygy::BuildError,
+        testing::fixtures::{TestModel, TestResource},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_provide() {
+        #[derive(Debug, Clone)]
+        struct Client(String);
+        #[derive(Debug, Clone)]
+        struct Ping;
+        #[derive(Debug, Clone)]
+        struct Pong;
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .provide(|resource: &TestResource, limit: &u32| {
+                Client(format!("{}:{limit}", resource.name))
+            })
+            .resource(8_u32)
+            .resource(TestResource {
+                name: "db".to_string(),
+            })
+            .build_checked()
+            .unwrap();
+        assert_eq!(syzygy.resource::<Client>().0, "db:8");
+
+        let missing = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .provide(|_: &TestResource| Ping)
+            .build_checked();
+        assert!(matches!(missing, Err(BuildError::MissingResource(_))));
+
+        let cycle = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .provide(|_: &Pong| Ping)
+            .provide(|_: &Ping| Pong)
+            .build_checked();
+        assert!(matches!(cycle, Err(BuildError::ProviderCycle(names)) if names.len() == 2));
+
+        let explicit = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .provide(|_: &u32| Client("provided".to_string()))
+            .resource(Client("explicit".to_string()))
+            .resource(TestResource {
+                name: "db".to_string(),
+            })
+            .provide(|resource: &TestResource| Client(resource.name.clone()))
+            .resource(1_u32)
+            .build();
+        assert_eq!(explicit.resource::<Client>().0, "explicit");
+
+        let reregistered = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .resource(1_u32)
+            .provide(|n: &u32| Client(n.to_string()))
+            .resource(2_u32)
+            .build();
+        assert_eq!(reregistered.resource::<Client>().0, "2");
+
+        let unresolved = std::panic::catch_unwind(|| {
+            Syzygy::builder()
+                .model(TestModel { counter: 0 })
+                .provide(|_: &TestResource| Ping)
+                .build()
+        });
+        assert!(unresolved.is_err());
+    }
+}
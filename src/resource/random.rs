@@ -0,0 +1,151 @@
+use std::{
+    ops::Range,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::ResourceAccess;
+
+const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Seedable SplitMix64 generator shared by every clone, so a run seeded through
+/// `SyzygyBuilder::seed` draws the same sequence as long as effects run in the same order.
+#[derive(Debug, Clone)]
+pub struct Random {
+    seed: u64,
+    state: Arc<AtomicU64>,
+}
+
+impl Random {
+    #[must_use]
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed,
+            state: Arc::new(AtomicU64::new(seed)),
+        }
+    }
+
+    /// Seeded from the clock, for runs that need not be reproducible.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_entropy() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::seeded(nanos)
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[must_use]
+    pub fn next_u64(&self) -> u64 {
+        let mut z = self
+            .state
+            .fetch_add(GAMMA, Ordering::Relaxed)
+            .wrapping_add(GAMMA);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn next_f64(&self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    /// Uniform in `range`, which must not be empty.
+    #[must_use]
+    pub fn range(&self, range: Range<u64>) -> u64 {
+        assert!(
+            !range.is_empty(),
+            "Random::range called with an empty range"
+        );
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// `true` with probability `p`.
+    #[must_use]
+    pub fn chance(&self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn shuffle<T>(&self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i as u64 + 1) as usize);
+        }
+    }
+}
+
+pub trait RandomAccess: ResourceAccess {
+    /// The shared `Random` resource, inserting one seeded from the clock if the builder
+    /// was not given a seed.
+    fn random(&self) -> Random {
+        self.resources().get_or_insert_with(Random::from_entropy)
+    }
+}
+
+impl<C: ResourceAccess> RandomAccess for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_seeded_random() {
+        let draw = |seed| {
+            let mut syzygy = Syzygy::builder()
+                .model(TestModel { counter: 0 })
+                .seed(seed)
+                .build();
+            for _ in 0..3 {
+                syzygy.dispatch(|syzygy: &mut Syzygy<TestModel>| {
+                    let roll = i32::try_from(syzygy.random().range(0..100)).unwrap();
+                    syzygy.model_mut().counter = syzygy.model().counter * 100 + roll;
+                });
+            }
+            syzygy.handle_effects();
+            syzygy.model().counter
+        };
+        assert_eq!(draw(7), draw(7));
+        assert_ne!(draw(7), draw(8));
+
+        let random = Random::seeded(1);
+        let mut items = [1, 2, 3, 4, 5];
+        random.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+        assert!((0.0..1.0).contains(&random.next_f64()));
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_random_inserted_once() {
+        let syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        let changes = Arc::new(AtomicU64::new(0));
+        syzygy.resources().subscribe::<Random, _>({
+            let changes = Arc::clone(&changes);
+            move |_| {
+                changes.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        let seeds: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| syzygy.random().seed()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(seeds.iter().all(|&seed| seed == syzygy.random().seed()));
+        assert_eq!(changes.load(Ordering::Relaxed), 0);
+    }
+}
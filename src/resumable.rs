@@ -0,0 +1,90 @@
+use std::panic::Location;
+
+use crate::{
+    dispatch::{DispatchEffect, Effect},
+    model::Model,
+    syzygy::Syzygy,
+};
+
+/// What a `ResumableEffect` step asks for next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Yield<S> {
+    /// Run again with `S` in the next `handle_effects` pass.
+    Continue(S),
+    Done,
+}
+
+/// Effect that does its work in steps, giving the loop back between them. Each step
+/// runs in its own `handle_effects` pass.
+pub trait ResumableEffect<M: Model>: Send + Sync + 'static {
+    type State: Send + Sync + 'static;
+
+    fn resume(&mut self, syzygy: &mut Syzygy<M>, state: Self::State) -> Yield<Self::State>;
+}
+
+pub trait ResumeEffect: DispatchEffect {
+    /// Dispatch the first step of `effect`, starting from `state`.
+    #[track_caller]
+    fn dispatch_resumable<E>(&self, effect: E, state: E::State)
+    where
+        E: ResumableEffect<Self::Model>,
+    {
+        self.send_effect(step(effect, state, Location::caller()));
+    }
+}
+
+impl<C: DispatchEffect> ResumeEffect for C {}
+
+fn step<M, E>(
+    mut effect: E,
+    state: E::State,
+    origin: &'static Location<'static>,
+) -> impl FnOnce(&mut Syzygy<M>) + Send + Sync
+where
+    M: Model,
+    E: ResumableEffect<M>,
+{
+    move |syzygy: &mut Syzygy<M>| {
+        if let Yield::Continue(state) = effect.resume(syzygy, state) {
+            let next = Effect::new(step(effect, state, origin)).with_origin(origin);
+            syzygy.resume_next_pass(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, testing::fixtures::TestModel};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_resumable_effect() {
+        struct CountTo(usize);
+
+        impl ResumableEffect<TestModel> for CountTo {
+            type State = usize;
+
+            fn resume(&mut self, cx: &mut Syzygy<TestModel>, step: usize) -> Yield<usize> {
+                cx.model_mut().counter += 1;
+                if step + 1 < self.0 {
+                    Yield::Continue(step + 1)
+                } else {
+                    Yield::Done
+                }
+            }
+        }
+
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        syzygy.dispatch_resumable(CountTo(3), 0);
+
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+        assert!(syzygy.handle_effects_max(u64::MAX).is_exhausted());
+        assert_eq!(syzygy.model().counter, 2);
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 3);
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 3);
+    }
+}
@@ -0,0 +1,495 @@
+use std::{
+    fmt,
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use rustc_hash::FxHashMap;
+
+use crate::context::Context;
+
+pub type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Executor backend used for every task and blocking job started by syzygy.
+pub trait RuntimeSpawner: Send + Sync + 'static {
+    fn spawn(&self, future: BoxFuture);
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>);
+
+    /// Whether spawning would currently succeed. Checked by `build_checked`.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Timer used by retries and schedules. Defaults to tokio's, which must be polled
+    /// inside a tokio runtime.
+    fn sleep(&self, duration: Duration) -> BoxFuture {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Spawns onto the ambient tokio runtime.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSpawner;
+
+impl RuntimeSpawner for TokioSpawner {
+    fn spawn(&self, future: BoxFuture) {
+        tokio::spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+        tokio::task::spawn_blocking(f);
+    }
+
+    fn is_available(&self) -> bool {
+        tokio::runtime::Handle::try_current().is_ok()
+    }
+}
+
+/// Spawns onto the runtime behind an explicit handle, from any thread.
+impl RuntimeSpawner for tokio::runtime::Handle {
+    fn spawn(&self, future: BoxFuture) {
+        tokio::runtime::Handle::spawn(self, future);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+        tokio::runtime::Handle::spawn_blocking(self, f);
+    }
+}
+
+/// Multi-threaded tokio runtime owned by the spawner, for programs without an async
+/// main. It shuts down without waiting for its tasks once the last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct OwnedRuntime(Arc<RuntimeOwner>);
+
+#[derive(Debug)]
+struct RuntimeOwner(Option<tokio::runtime::Runtime>);
+
+impl Drop for RuntimeOwner {
+    fn drop(&mut self) {
+        // Tasks may hold the last clone, and a runtime can't be dropped from its own
+        // worker threads.
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl OwnedRuntime {
+    /// Errors if `worker_threads` is zero or the runtime fails to start.
+    pub fn new(worker_threads: usize) -> std::io::Result<Self> {
+        if worker_threads == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "owned runtime needs at least one worker thread",
+            ));
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(worker_threads)
+            .thread_name("syzygy-runtime")
+            .enable_all()
+            .build()?;
+        Ok(Self(Arc::new(RuntimeOwner(Some(runtime)))))
+    }
+
+    #[must_use]
+    pub fn handle(&self) -> &tokio::runtime::Handle {
+        self.0
+            .0
+            .as_ref()
+            .expect("Runtime should live as long as its owner")
+            .handle()
+    }
+}
+
+impl RuntimeSpawner for OwnedRuntime {
+    fn spawn(&self, future: BoxFuture) {
+        self.handle().spawn(future);
+    }
+
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+        self.handle().spawn_blocking(f);
+    }
+}
+
+/// Passed to task hooks to tell spawns apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub blocking: bool,
+    /// Where the task was spawned from.
+    pub origin: &'static Location<'static>,
+}
+
+impl TaskInfo {
+    fn new(blocking: bool, origin: &'static Location<'static>) -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self {
+            id: NEXT.fetch_add(1, Ordering::Relaxed),
+            blocking,
+            origin,
+        }
+    }
+}
+
+type SpawnHook = Arc<dyn Fn(&TaskInfo) + Send + Sync>;
+type CompleteHook = Arc<dyn Fn(&TaskInfo, Duration) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub(crate) struct TaskHooks {
+    pub(crate) on_spawn: Option<SpawnHook>,
+    pub(crate) on_complete: Option<CompleteHook>,
+}
+
+impl TaskHooks {
+    fn is_empty(&self) -> bool {
+        self.on_spawn.is_none() && self.on_complete.is_none()
+    }
+
+    fn spawned(&self, blocking: bool, origin: &'static Location<'static>) -> TaskInfo {
+        let info = TaskInfo::new(blocking, origin);
+        if let Some(on_spawn) = &self.on_spawn {
+            on_spawn(&info);
+        }
+        info
+    }
+
+    fn completed(&self, info: &TaskInfo, started: Instant) {
+        if let Some(on_complete) = &self.on_complete {
+            on_complete(info, started.elapsed());
+        }
+    }
+}
+
+/// Number of unfinished tasks spawned from one call site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InflightSite {
+    pub origin: &'static Location<'static>,
+    pub count: usize,
+}
+
+/// Unfinished tasks per spawn site, see `SyzygyBuilder::track_inflight_tasks`.
+#[derive(Debug)]
+pub(crate) struct Inflight {
+    warn_threshold: usize,
+    sites: Mutex<FxHashMap<&'static Location<'static>, usize>>,
+}
+
+impl Inflight {
+    pub(crate) fn new(warn_threshold: usize) -> Self {
+        Self {
+            warn_threshold,
+            sites: Mutex::default(),
+        }
+    }
+
+    fn enter(self: &Arc<Self>, origin: &'static Location<'static>) -> InflightGuard {
+        let mut sites = self.sites.lock().expect("Failed to acquire lock");
+        let count = sites.entry(origin).or_default();
+        *count += 1;
+        if *count == self.warn_threshold + 1 {
+            log::warn!(
+                "More than {} tasks spawned at {origin} are in flight, possible leak",
+                self.warn_threshold
+            );
+        }
+        InflightGuard {
+            inflight: Arc::clone(self),
+            origin,
+        }
+    }
+
+    pub(crate) fn report(&self) -> Vec<InflightSite> {
+        let sites = self.sites.lock().expect("Failed to acquire lock");
+        let mut report: Vec<_> = sites
+            .iter()
+            .map(|(origin, count)| InflightSite {
+                origin,
+                count: *count,
+            })
+            .collect();
+        report.sort_by_key(|site| std::cmp::Reverse(site.count));
+        report
+    }
+}
+
+/// Counts a task as finished when dropped, also when the task is cancelled.
+struct InflightGuard {
+    inflight: Arc<Inflight>,
+    origin: &'static Location<'static>,
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        let mut sites = self.inflight.sites.lock().expect("Failed to acquire lock");
+        if let Some(count) = sites.get_mut(self.origin) {
+            *count -= 1;
+            if *count == 0 {
+                sites.remove(self.origin);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Spawner {
+    runtime: Arc<dyn RuntimeSpawner>,
+    hooks: TaskHooks,
+    pub(crate) inflight: Option<Arc<Inflight>>,
+}
+
+impl Spawner {
+    pub fn new<S: RuntimeSpawner>(spawner: S) -> Self {
+        Self {
+            runtime: Arc::new(spawner),
+            hooks: TaskHooks::default(),
+            inflight: None,
+        }
+    }
+
+    /// Swap the backend, keeping any task hooks.
+    pub(crate) fn set_runtime<S: RuntimeSpawner>(&mut self, spawner: S) {
+        self.runtime = Arc::new(spawner);
+    }
+
+    pub(crate) fn hooks_mut(&mut self) -> &mut TaskHooks {
+        &mut self.hooks
+    }
+
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        self.runtime.is_available()
+    }
+
+    /// Wait for `duration` on the backend's timer.
+    #[must_use]
+    pub fn sleep(&self, duration: Duration) -> BoxFuture {
+        self.runtime.sleep(duration)
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_at(Location::caller(), future);
+    }
+
+    /// Like `spawn`, but counts the task towards `origin` in `Syzygy::inflight_tasks`.
+    pub(crate) fn spawn_at<F>(&self, origin: &'static Location<'static>, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self.hooks.is_empty() && self.inflight.is_none() {
+            self.runtime.spawn(Box::pin(future));
+            return;
+        }
+        let guard = self
+            .inflight
+            .as_ref()
+            .map(|inflight| inflight.enter(origin));
+        let hooks = self.hooks.clone();
+        let info = hooks.spawned(false, origin);
+        self.runtime.spawn(Box::pin(async move {
+            let _guard = guard;
+            let started = Instant::now();
+            future.await;
+            hooks.completed(&info, started);
+        }));
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn spawn_blocking<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.spawn_blocking_at(Location::caller(), f);
+    }
+
+    pub(crate) fn spawn_blocking_at<F>(&self, origin: &'static Location<'static>, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if self.hooks.is_empty() && self.inflight.is_none() {
+            self.runtime.spawn_blocking(Box::new(f));
+            return;
+        }
+        let guard = self
+            .inflight
+            .as_ref()
+            .map(|inflight| inflight.enter(origin));
+        let hooks = self.hooks.clone();
+        let info = hooks.spawned(true, origin);
+        self.runtime.spawn_blocking(Box::new(move || {
+            let _guard = guard;
+            let started = Instant::now();
+            f();
+            hooks.completed(&info, started);
+        }));
+    }
+}
+
+impl Default for Spawner {
+    fn default() -> Self {
+        Self::new(TokioSpawner)
+    }
+}
+
+impl fmt::Debug for Spawner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Spawner").finish_non_exhaustive()
+    }
+}
+
+pub trait RuntimeAccess: Context {
+    fn spawner(&self) -> &Spawner;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        syzygy::BuildError,
+        testing::fixtures::{TestModel, increment},
+    };
+    use tokio::sync::oneshot;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_custom_spawner() {
+        #[derive(Default, Clone)]
+        struct QueueSpawner(Arc<Mutex<Vec<BoxFuture>>>);
+
+        impl RuntimeSpawner for QueueSpawner {
+            fn spawn(&self, future: BoxFuture) {
+                self.0.lock().unwrap().push(future);
+            }
+
+            fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+                f();
+            }
+        }
+
+        let spawner = QueueSpawner::default();
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .spawner(spawner.clone())
+            .build();
+
+        syzygy.task(|cx| async move { cx.dispatch(increment) });
+        syzygy.spawn(|cx| cx.dispatch(increment));
+        syzygy.handle_effects();
+        assert_eq!(spawner.0.lock().unwrap().len(), 1);
+
+        let futures = std::mem::take(&mut *spawner.0.lock().unwrap());
+        for future in futures {
+            future.await;
+        }
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 2);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_instrumentation_hooks() {
+        use std::time::Duration;
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (start_log, end_log, spawn_log, done_log) = (
+            Arc::clone(&log),
+            Arc::clone(&log),
+            Arc::clone(&log),
+            Arc::clone(&log),
+        );
+        let (done_tx, done_rx) = oneshot::channel();
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .on_effect_start(move |_| start_log.lock().unwrap().push("start"))
+            .on_effect_end(move |_, _| end_log.lock().unwrap().push("end"))
+            .on_task_spawn(move |info| {
+                assert!(!info.blocking);
+                spawn_log.lock().unwrap().push("spawn");
+            })
+            .on_task_complete(move |_, _| {
+                done_log.lock().unwrap().push("complete");
+            })
+            .spawner(crate::runtime::TokioSpawner)
+            .build();
+
+        syzygy.task(|_| async move {
+            let _ = done_tx.send(());
+        });
+        syzygy.handle_effects();
+        done_rx.await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(*log.lock().unwrap(), ["start", "spawn", "end", "complete"]);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_inflight_tasks() {
+        let mut syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .track_inflight_tasks(1)
+            .build();
+        let (tx, rx) = tokio::sync::watch::channel(false);
+        for _ in 0..2 {
+            let mut rx = rx.clone();
+            syzygy.task(|_| async move {
+                let _ = rx.wait_for(|done| *done).await;
+            });
+        }
+        syzygy.task(|cx| async move { cx.dispatch(increment) });
+        syzygy.handle_effects();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        let inflight = syzygy.inflight_tasks();
+        assert_eq!(inflight.len(), 1);
+        assert_eq!(inflight[0].count, 2);
+        assert!(inflight[0].origin.file().ends_with("runtime.rs"));
+
+        tx.send(true).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(syzygy.inflight_tasks().is_empty());
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_owned_runtime() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .with_owned_runtime(1)
+            .build_checked()
+            .unwrap();
+
+        syzygy.task(|cx| async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            cx.dispatch(increment);
+        });
+        let start = Instant::now();
+        while syzygy.model().counter == 0 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(1));
+            syzygy.handle_effects();
+        }
+
+        assert_eq!(syzygy.model().counter, 1);
+
+        let no_workers = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .with_owned_runtime(0)
+            .build_checked();
+        assert!(matches!(no_workers, Err(BuildError::OwnedRuntime(_))));
+    }
+}
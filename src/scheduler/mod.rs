@@ -0,0 +1,181 @@
+use std::{
+    panic::Location,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, EffectFn, EffectsTx, SendError},
+    model::Model,
+    runtime::{RuntimeAccess, Spawner},
+};
+
+mod poll;
+mod reconnect;
+
+pub use poll::Poller;
+pub use reconnect::{Backoff, Connection, ReconnectEffect, ReconnectHandle};
+
+pub trait Schedule: Send + 'static {
+    /// Delay until the next run, `None` ends the schedule.
+    fn next_delay(&mut self) -> Option<Duration>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Every {
+    period: Duration,
+    times: Option<usize>,
+}
+
+#[must_use]
+pub fn every(period: Duration) -> Every {
+    Every {
+        period,
+        times: None,
+    }
+}
+
+impl Every {
+    #[must_use]
+    pub fn times(mut self, times: usize) -> Self {
+        self.times = Some(times);
+        self
+    }
+}
+
+impl Schedule for Every {
+    fn next_delay(&mut self) -> Option<Duration> {
+        match &mut self.times {
+            Some(0) => None,
+            Some(times) => {
+                *times -= 1;
+                Some(self.period)
+            }
+            None => Some(self.period),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ScheduleState {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    cancel: Notify,
+}
+
+/// Control handle for a running schedule. Dropping it leaves the schedule running.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleHandle(Arc<ScheduleState>);
+
+impl ScheduleHandle {
+    pub fn pause(&self) {
+        self.0.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.0.paused.store(false, Ordering::Release);
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Release);
+        self.0.cancel.notify_one();
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.0.paused.load(Ordering::Acquire)
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Acquire)
+    }
+}
+
+pub trait ScheduleEffect: DispatchEffect + RuntimeAccess {
+    /// Dispatch an effect produced by `factory` on every tick of `schedule`.
+    #[track_caller]
+    fn schedule<S, F, E>(&self, schedule: S, factory: F) -> ScheduleHandle
+    where
+        S: Schedule,
+        F: FnMut() -> E + Send + 'static,
+        E: EffectFn<Self::Model>,
+    {
+        let handle = ScheduleHandle::default();
+        let state = Arc::clone(&handle.0);
+        let tx = self.effects_tx().clone();
+        let origin = Location::caller();
+        let spawner = self.spawner().clone();
+        self.spawner()
+            .spawn(run_schedule(schedule, factory, tx, spawner, state, origin));
+        handle
+    }
+}
+
+impl<C: DispatchEffect + RuntimeAccess> ScheduleEffect for C {}
+
+async fn run_schedule<M, S, F, E>(
+    mut schedule: S,
+    mut factory: F,
+    tx: EffectsTx<M>,
+    spawner: Spawner,
+    state: Arc<ScheduleState>,
+    origin: &'static Location<'static>,
+) where
+    M: Model,
+    S: Schedule,
+    F: FnMut() -> E + Send + 'static,
+    E: EffectFn<M>,
+{
+    while let Some(delay) = schedule.next_delay() {
+        tokio::select! {
+            () = spawner.sleep(delay) => {}
+            () = state.cancel.notified() => {}
+        }
+        if state.cancelled.load(Ordering::Acquire) {
+            break;
+        }
+        if state.paused.load(Ordering::Acquire) {
+            continue;
+        }
+        if matches!(
+            tx.send(Effect::new(factory()).with_origin(origin)),
+            Err(SendError::Disconnected(_))
+        ) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, increment},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(start_paused = true)]
+    async fn test_schedule() {
+        use std::time::Duration;
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.schedule(every(Duration::from_millis(10)).times(3), || increment);
+        let handle = syzygy.schedule(every(Duration::from_millis(10)), || increment);
+        handle.cancel();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        syzygy.handle_effects();
+
+        assert!(handle.is_cancelled());
+        assert_eq!(syzygy.model().counter, 3);
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    fmt,
+    future::Future,
+    panic::Location,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, EffectsTx, SendError},
+    model::Model,
+    resource::{ResourceAccess, ResourceModify},
+    runtime::{RuntimeAccess, Spawner},
+    syzygy::Syzygy,
+};
+
+use super::{ScheduleHandle, ScheduleState};
+
+/// Periodically fetches a value and keeps it as a resource, see `Poller::into_resource`.
+#[derive(Debug, Clone, Copy)]
+pub struct Poller<F> {
+    interval: Duration,
+    fetch: F,
+}
+
+impl<F> Poller<F> {
+    pub fn new(interval: Duration, fetch: F) -> Self {
+        Self { interval, fetch }
+    }
+
+    /// Run `fetch` right away and then every interval, replacing the `T` resource when
+    /// the result differs from it. Only actual changes reach `on_resource_changed`
+    /// listeners; failed fetches are logged and leave the resource as it is.
+    #[track_caller]
+    pub fn into_resource<C, T, E, Fut>(self, cx: &C) -> ScheduleHandle
+    where
+        C: DispatchEffect + RuntimeAccess,
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Clone + PartialEq + Send + Sync + 'static,
+        E: fmt::Display + Send + 'static,
+    {
+        let handle = ScheduleHandle::default();
+        let state = Arc::clone(&handle.0);
+        let tx = cx.effects_tx().clone();
+        let origin = Location::caller();
+        let spawner = cx.spawner().clone();
+        cx.spawner()
+            .spawn(run_poller(self, tx, spawner, state, origin));
+        handle
+    }
+}
+
+async fn run_poller<M, F, T, E, Fut>(
+    mut poller: Poller<F>,
+    tx: EffectsTx<M>,
+    spawner: Spawner,
+    state: Arc<ScheduleState>,
+    origin: &'static Location<'static>,
+) where
+    M: Model,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    T: Clone + PartialEq + Send + Sync + 'static,
+    E: fmt::Display + Send + 'static,
+{
+    let mut delay = Duration::ZERO;
+    loop {
+        tokio::select! {
+            () = spawner.sleep(delay) => {}
+            () = state.cancel.notified() => {}
+        }
+        delay = poller.interval;
+        if state.cancelled.load(Ordering::Acquire) {
+            break;
+        }
+        if state.paused.load(Ordering::Acquire) {
+            continue;
+        }
+        let value = match (poller.fetch)().await {
+            Ok(value) => value,
+            Err(error) => {
+                log::warn!("Poll from {origin} failed: {error}");
+                continue;
+            }
+        };
+        let effect = Effect::new(move |syzygy: &mut Syzygy<M>| {
+            let current = syzygy.resources().get_arc::<T>();
+            if current.as_deref() != Some(&value) {
+                syzygy.insert_resource(value);
+            }
+        })
+        .with_origin(origin);
+        if matches!(tx.send(effect), Err(SendError::Disconnected(_))) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, resource::ResourceChanged, testing::fixtures::TestModel};
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(start_paused = true)]
+    async fn test_poller() {
+        use std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::Duration,
+        };
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        syzygy.on_resource_changed(|cx, _: &ResourceChanged<u32>| cx.model_mut().counter += 1);
+        let polls = Arc::new(AtomicUsize::new(0));
+        let handle = Poller::new(Duration::from_millis(5), {
+            let polls = Arc::clone(&polls);
+            move || {
+                let poll = polls.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match poll {
+                        0 | 1 => Ok(1_u32),
+                        2 => Err("unreachable"),
+                        _ => Ok(2),
+                    }
+                }
+            }
+        })
+        .into_resource(&syzygy);
+
+        while polls.load(Ordering::SeqCst) < 5 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            syzygy.handle_effects();
+        }
+        handle.cancel();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        syzygy.handle_effects();
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.resource::<u32>(), 2);
+        assert_eq!(syzygy.model().counter, 2);
+    }
+}
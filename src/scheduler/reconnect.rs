@@ -0,0 +1,271 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, EffectsTx, SendError},
+    model::Model,
+    resource::ResourceModify,
+    runtime::{RuntimeAccess, Spawner},
+    syzygy::Syzygy,
+};
+
+use super::Schedule;
+
+/// Exponential backoff: `initial`, then doubled on every failure up to `max`.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Backoff {
+    #[must_use]
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            current: initial,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+impl Schedule for Backoff {
+    fn next_delay(&mut self) -> Option<Duration> {
+        let delay = self.current;
+        self.current = self.current.saturating_mul(2).min(self.max);
+        Some(delay)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connection {
+    Connected,
+    Disconnected,
+}
+
+#[derive(Debug, Default)]
+struct ReconnectState {
+    connected: AtomicBool,
+    lost: Notify,
+    cancelled: AtomicBool,
+    cancel: Notify,
+}
+
+/// Control handle for a `reconnect` loop. Dropping it leaves the loop running.
+#[derive(Debug, Clone, Default)]
+pub struct ReconnectHandle(Arc<ReconnectState>);
+
+impl ReconnectHandle {
+    /// Report the connection as broken. Its resource is removed and a new one connected.
+    pub fn disconnected(&self) {
+        if self.0.connected.swap(false, Ordering::AcqRel) {
+            self.0.lost.notify_one();
+        }
+    }
+
+    /// Stop reconnecting. The current resource, if any, is removed.
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Release);
+        self.0.cancel.notify_one();
+    }
+
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.0.connected.load(Ordering::Acquire)
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Acquire)
+    }
+}
+
+type OnChange<M> = Arc<dyn Fn(&mut Syzygy<M>, Connection) + Send + Sync>;
+
+pub trait ReconnectEffect: DispatchEffect + RuntimeAccess {
+    /// Keep the resource produced by `connect` in `Resources`. Failed attempts are
+    /// retried after `backoff`; `on_change` runs on the loop after the resource was
+    /// inserted or removed.
+    #[track_caller]
+    fn reconnect<T, Err, F, Fut, C>(
+        &self,
+        backoff: Backoff,
+        connect: F,
+        on_change: C,
+    ) -> ReconnectHandle
+    where
+        T: Clone + Send + Sync + 'static,
+        Err: fmt::Display + Send + 'static,
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T, Err>> + Send + 'static,
+        C: Fn(&mut Syzygy<Self::Model>, Connection) + Send + Sync + 'static,
+    {
+        let handle = ReconnectHandle::default();
+        let state = Arc::clone(&handle.0);
+        let tx = self.effects_tx().clone();
+        let spawner = self.spawner().clone();
+        self.spawner().spawn(run_reconnect(
+            backoff,
+            connect,
+            Arc::new(on_change),
+            tx,
+            spawner,
+            state,
+        ));
+        handle
+    }
+}
+
+impl<C: DispatchEffect + RuntimeAccess> ReconnectEffect for C {}
+
+async fn run_reconnect<M, T, Err, F, Fut>(
+    mut backoff: Backoff,
+    mut connect: F,
+    on_change: OnChange<M>,
+    tx: EffectsTx<M>,
+    spawner: Spawner,
+    state: Arc<ReconnectState>,
+) where
+    M: Model,
+    T: Clone + Send + Sync + 'static,
+    Err: fmt::Display + Send + 'static,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, Err>> + Send + 'static,
+{
+    let notify = |connection: Connection, value: Option<T>| {
+        let on_change = Arc::clone(&on_change);
+        tx.send(Effect::new(move |syzygy: &mut Syzygy<M>| {
+            match value {
+                Some(value) => {
+                    syzygy.insert_resource(value);
+                }
+                None => {
+                    syzygy.remove_resource::<T>();
+                }
+            }
+            on_change(syzygy, connection);
+        }))
+    };
+    while !state.cancelled.load(Ordering::Acquire) {
+        let value = tokio::select! {
+            result = connect() => result,
+            () = state.cancel.notified() => break,
+        };
+        match value {
+            Ok(value) => {
+                backoff.reset();
+                state.connected.store(true, Ordering::Release);
+                if matches!(
+                    notify(Connection::Connected, Some(value)),
+                    Err(SendError::Disconnected(_))
+                ) {
+                    return;
+                }
+                tokio::select! {
+                    () = state.lost.notified() => {}
+                    () = state.cancel.notified() => {}
+                }
+                state.connected.store(false, Ordering::Release);
+                if matches!(
+                    notify(Connection::Disconnected, None),
+                    Err(SendError::Disconnected(_))
+                ) {
+                    return;
+                }
+            }
+            Err(error) => {
+                log::warn!("Reconnect attempt failed: {error}");
+                let delay = backoff.next_delay().unwrap_or(backoff.max);
+                tokio::select! {
+                    () = spawner.sleep(delay) => {}
+                    () = state.cancel.notified() => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, TestResource},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(start_paused = true)]
+    async fn test_reconnect() {
+        use std::{
+            sync::atomic::{AtomicUsize, Ordering},
+            time::Duration,
+        };
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let handle = syzygy.reconnect(
+            Backoff::new(Duration::from_millis(1), Duration::from_millis(4)),
+            {
+                let attempts = Arc::clone(&attempts);
+                move || {
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                    async move {
+                        if attempt % 3 == 2 {
+                            Ok(TestResource {
+                                name: format!("conn-{attempt}"),
+                            })
+                        } else {
+                            Err("refused")
+                        }
+                    }
+                }
+            },
+            |syzygy, connection| match connection {
+                Connection::Connected => syzygy.model_mut().counter += 1,
+                Connection::Disconnected => syzygy.model_mut().counter += 10,
+            },
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        syzygy.handle_effects();
+        assert!(handle.is_connected());
+        assert_eq!(syzygy.resource::<TestResource>().name, "conn-2");
+        assert_eq!(syzygy.model().counter, 1);
+
+        handle.disconnected();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        syzygy.handle_effects();
+        assert_eq!(syzygy.resource::<TestResource>().name, "conn-5");
+        assert_eq!(syzygy.model().counter, 12);
+
+        handle.cancel();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        syzygy.handle_effects();
+        assert!(syzygy.try_resource::<TestResource>().is_none());
+        assert_eq!(attempts.load(Ordering::SeqCst), 6);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_backoff_saturates() {
+        let mut backoff = Backoff::new(Duration::MAX / 2 + Duration::from_secs(1), Duration::MAX);
+        backoff.next_delay();
+        assert_eq!(backoff.next_delay(), Some(Duration::MAX));
+        assert_eq!(backoff.next_delay(), Some(Duration::MAX));
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    context::{FromContext, r#async::AsyncContext},
+    dispatch::DispatchEffect,
+    model::Model,
+    runtime::{RuntimeAccess, Spawner},
+};
+
+#[derive(Debug, Default)]
+struct ScopeState {
+    pending: AtomicUsize,
+    done: Notify,
+    cancelled: AtomicBool,
+    cancel: Notify,
+}
+
+impl ScopeState {
+    async fn wait_cancelled(&self) {
+        loop {
+            let mut notified = pin!(self.cancel.notified());
+            notified.as_mut().enable();
+            if self.cancelled.load(Ordering::Acquire) {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Decrements the pending count when a scoped task finishes, panics or is dropped.
+struct TaskGuard(Arc<ScopeState>);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if self.0.pending.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.done.notify_waiters();
+        }
+    }
+}
+
+/// Spawns tasks that are tracked by the enclosing `ScopeEffect::scope` call.
+#[derive(Debug)]
+pub struct Scope<M: Model> {
+    state: Arc<ScopeState>,
+    spawner: Spawner,
+    _model: std::marker::PhantomData<fn() -> M>,
+}
+
+impl<M: Model> Scope<M> {
+    fn guard(&self) -> TaskGuard {
+        self.state.pending.fetch_add(1, Ordering::AcqRel);
+        TaskGuard(Arc::clone(&self.state))
+    }
+
+    /// Run `f` on a blocking thread. Blocking tasks always run to completion.
+    #[track_caller]
+    pub fn spawn<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let guard = self.guard();
+        self.spawner.spawn_blocking(move || {
+            let _guard = guard;
+            f();
+        });
+    }
+
+    /// Run `future` on the runtime. It is dropped at its next await point once the scope is cancelled.
+    #[track_caller]
+    pub fn spawn_async<F>(&self, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let guard = self.guard();
+        self.spawner.spawn(async move {
+            let state = Arc::clone(&guard.0);
+            tokio::select! {
+                () = future => {}
+                () = state.wait_cancelled() => {}
+            }
+            drop(guard);
+        });
+    }
+}
+
+/// Handle to the tasks spawned within a scope.
+#[derive(Debug, Clone)]
+pub struct ScopeHandle(Arc<ScopeState>);
+
+impl ScopeHandle {
+    /// Resolves once every task spawned in the scope has completed or been cancelled.
+    pub async fn join(&self) {
+        loop {
+            let mut notified = pin!(self.0.done.notified());
+            notified.as_mut().enable();
+            if self.is_finished() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::Release);
+        self.0.cancel.notify_waiters();
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Acquire)
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.0.pending.load(Ordering::Acquire) == 0
+    }
+}
+
+pub trait ScopeEffect: DispatchEffect + RuntimeAccess {
+    /// Run `f` with a `Scope` whose tasks are all tracked by the returned handle.
+    fn scope<F>(&self, f: F) -> ScopeHandle
+    where
+        F: FnOnce(&Scope<Self::Model>, AsyncContext<Self::Model>),
+        AsyncContext<Self::Model>: FromContext<Self>,
+    {
+        let scope = Scope {
+            state: Arc::default(),
+            spawner: self.spawner().clone(),
+            _model: std::marker::PhantomData,
+        };
+        f(&scope, AsyncContext::from_context(self));
+        ScopeHandle(scope.state)
+    }
+}
+
+impl<C: DispatchEffect + RuntimeAccess> ScopeEffect for C {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        testing::fixtures::{TestModel, increment},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_scope() {
+        use std::sync::atomic::{AtomicI32, Ordering};
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let counter = Arc::new(AtomicI32::new(0));
+
+        let counter_clone = Arc::clone(&counter);
+        let handle = syzygy.scope(move |scope, task_cx| {
+            scope.spawn(move || {
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+            });
+            scope.spawn_async(async move {
+                task_cx.dispatch(increment);
+            });
+        });
+        handle.join().await;
+        assert!(handle.is_finished());
+
+        syzygy.handle_effects();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert_eq!(syzygy.model().counter, 1);
+
+        let handle = syzygy.scope(|scope, _| {
+            scope.spawn_async(std::future::pending());
+        });
+        assert!(!handle.is_finished());
+        handle.cancel();
+        handle.join().await;
+        assert!(handle.is_cancelled());
+    }
+}
@@ -0,0 +1,96 @@
+use std::{io, panic::Location};
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, EffectFn, SendError},
+    runtime::RuntimeAccess,
+    syzygy::Syzygy,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Signal {
+    /// SIGINT, or console ctrl-c on Windows.
+    Interrupt,
+    /// SIGTERM. Never delivered on Windows.
+    Terminate,
+}
+
+#[cfg(unix)]
+struct Listener {
+    interrupt: tokio::signal::unix::Signal,
+    terminate: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl Listener {
+    fn new() -> io::Result<Self> {
+        use tokio::signal::unix::{SignalKind, signal};
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    async fn recv(&mut self) -> Option<Signal> {
+        tokio::select! {
+            s = self.interrupt.recv() => s.map(|()| Signal::Interrupt),
+            s = self.terminate.recv() => s.map(|()| Signal::Terminate),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct Listener;
+
+#[cfg(not(unix))]
+impl Listener {
+    #[allow(clippy::unnecessary_wraps)]
+    fn new() -> io::Result<Self> {
+        Ok(Self)
+    }
+
+    async fn recv(&mut self) -> Option<Signal> {
+        tokio::signal::ctrl_c()
+            .await
+            .ok()
+            .map(|()| Signal::Interrupt)
+    }
+}
+
+pub trait SignalEffect: DispatchEffect + RuntimeAccess {
+    /// Dispatch the effect produced by `factory` for every received signal.
+    /// Handlers are installed before returning and need a tokio runtime.
+    #[track_caller]
+    fn on_signal<F, E>(&self, mut factory: F) -> io::Result<()>
+    where
+        F: FnMut(Signal) -> E + Send + 'static,
+        E: EffectFn<Self::Model>,
+    {
+        let mut listener = Listener::new()?;
+        let tx = self.effects_tx().clone();
+        let origin = Location::caller();
+        self.spawner().spawn(async move {
+            while let Some(signal) = listener.recv().await {
+                if matches!(
+                    tx.send(Effect::new(factory(signal)).with_origin(origin)),
+                    Err(SendError::Disconnected(_))
+                ) {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Request a graceful `run_async` stop on SIGINT/SIGTERM.
+    #[track_caller]
+    fn shutdown_on_signal(&self) -> io::Result<()> {
+        self.on_signal(|signal| {
+            move |syzygy: &mut Syzygy<Self::Model>| {
+                log::info!("Received {signal:?}, shutting down");
+                syzygy.shutdown();
+            }
+        })
+    }
+}
+
+impl<C: DispatchEffect + RuntimeAccess> SignalEffect for C {}
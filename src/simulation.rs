@@ -0,0 +1,125 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    dispatch::{Effect, EffectMeta},
+    model::Model,
+    runtime::{BoxFuture, RuntimeSpawner, TaskInfo},
+    syzygy::Syzygy,
+};
+
+/// Drops every task instead of running it.
+struct StubSpawner;
+
+impl RuntimeSpawner for StubSpawner {
+    fn spawn(&self, _future: BoxFuture) {}
+
+    fn spawn_blocking(&self, _f: Box<dyn FnOnce() + Send + 'static>) {}
+}
+
+/// Outcome of `Syzygy::simulate`.
+#[derive(Debug, Clone)]
+pub struct SimulationReport<M> {
+    /// The model after the effects and their follow-ups ran.
+    pub model: M,
+    /// Every effect that ran, follow-ups included, in order.
+    pub effects: Vec<EffectMeta>,
+    /// Tasks and blocking jobs that would have been spawned.
+    pub tasks: Vec<TaskInfo>,
+}
+
+impl<M: Model + Clone> Syzygy<M> {
+    /// Run `effects` and everything they dispatch against a clone of the model, with task
+    /// spawning stubbed out, and leave this loop untouched. Resources can be read but not
+    /// written; an effect that tries panics like any other failing effect.
+    pub fn simulate<I>(&self, effects: I) -> SimulationReport<M>
+    where
+        I: IntoIterator<Item = Effect<M>>,
+    {
+        let ran = Arc::new(Mutex::new(Vec::new()));
+        let spawned = Arc::new(Mutex::new(Vec::new()));
+        let mut simulation = Syzygy::builder()
+            .model(self.model.clone())
+            .spawner(StubSpawner)
+            .catch_effect_panics(self.catch_effect_panics)
+            .audit_log({
+                let ran = Arc::clone(&ran);
+                move |meta| {
+                    ran.lock()
+                        .expect("Failed to acquire lock")
+                        .push(meta.clone());
+                }
+            })
+            .on_task_spawn({
+                let spawned = Arc::clone(&spawned);
+                move |info| {
+                    spawned.lock().expect("Failed to acquire lock").push(*info);
+                }
+            })
+            .build();
+        simulation.resources = self.resources.read_only();
+        for effect in effects {
+            simulation.effects_bus.tx.send_or_drop(effect);
+        }
+        simulation.handle_effects();
+
+        let effects = std::mem::take(&mut *ran.lock().expect("Failed to acquire lock"));
+        let tasks = std::mem::take(&mut *spawned.lock().expect("Failed to acquire lock"));
+        SimulationReport {
+            model: simulation.model,
+            effects,
+            tasks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prelude::*,
+        resource::ResourceChanged,
+        testing::fixtures::{TestModel, increment},
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_simulate() {
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 1 }).build();
+        let report = syzygy.simulate([
+            Effect::new(increment),
+            Effect::new(|cx: &mut Syzygy<TestModel>| {
+                cx.dispatch(increment);
+                cx.task(|cx| async move { cx.dispatch(increment) });
+            }),
+        ]);
+
+        assert_eq!(report.model.counter, 3);
+        assert_eq!(report.effects.len(), 4);
+        assert_eq!(report.tasks.len(), 1);
+        assert!(report.tasks[0].origin.file().ends_with("simulation.rs"));
+
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_simulate_isolates_resources() {
+        let mut syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .resource(1_u32)
+            .catch_effect_panics(true)
+            .build();
+        syzygy.on_resource_changed(|cx, _: &ResourceChanged<u32>| cx.model_mut().counter += 1);
+        let report = syzygy.simulate([Effect::new(|cx: &mut Syzygy<TestModel>| {
+            let value = cx.resource::<u32>();
+            cx.model_mut().counter += 10;
+            cx.insert_resource(value + 1);
+        })]);
+
+        assert_eq!(report.model.counter, 10);
+        syzygy.handle_effects();
+        assert_eq!(syzygy.resource::<u32>(), 1);
+        assert_eq!(syzygy.model().counter, 0);
+    }
+}
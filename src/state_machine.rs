@@ -0,0 +1,256 @@
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    panic::Location,
+    sync::Arc,
+};
+
+use crate::{
+    dispatch::{Effect, EffectFn, EffectsTx},
+    model::{Model, ModelModify},
+    syzygy::Syzygy,
+};
+
+type Guard = Arc<dyn Fn(&dyn Any) -> bool + Send + Sync>;
+type Action<M> = Arc<dyn Fn(&dyn Any) -> Effect<M> + Send + Sync>;
+type Hook<M> = Arc<dyn Fn() -> Effect<M> + Send + Sync>;
+
+struct Transition<S, M: Model> {
+    input: TypeId,
+    from: S,
+    to: S,
+    guard: Guard,
+    action: Action<M>,
+}
+
+impl<S: Copy, M: Model> Clone for Transition<S, M> {
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input,
+            from: self.from,
+            to: self.to,
+            guard: Arc::clone(&self.guard),
+            action: Arc::clone(&self.action),
+        }
+    }
+}
+
+/// Returned by `StateMachine::fire` when an input caused a transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transitioned<S> {
+    pub from: S,
+    pub to: S,
+}
+
+/// Finite state machine meant to live in the model. Transitions dispatch the exit
+/// effects of the old state, the transition action and the entry effects of the new
+/// state, in that order.
+pub struct StateMachine<S, M: Model> {
+    state: S,
+    transitions: Vec<Transition<S, M>>,
+    on_enter: Vec<(S, Hook<M>)>,
+    on_exit: Vec<(S, Hook<M>)>,
+}
+
+impl<S, M> StateMachine<S, M>
+where
+    S: Copy + PartialEq + Send + Sync + 'static,
+    M: Model,
+{
+    pub fn new(initial: S) -> Self {
+        Self {
+            state: initial,
+            transitions: Vec::new(),
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+
+    /// Move from `from` to `to` on input `I` when `guard` accepts it. The first matching
+    /// transition in registration order wins.
+    #[must_use]
+    pub fn on<I, G, A, E>(mut self, from: S, to: S, guard: G, action: A) -> Self
+    where
+        I: 'static,
+        G: Fn(&I) -> bool + Send + Sync + 'static,
+        A: Fn(&I) -> E + Send + Sync + 'static,
+        E: EffectFn<M>,
+    {
+        self.transitions.push(Transition {
+            input: TypeId::of::<I>(),
+            from,
+            to,
+            guard: Arc::new(move |input| input.downcast_ref::<I>().is_some_and(&guard)),
+            action: Arc::new(move |input| {
+                let input = input
+                    .downcast_ref::<I>()
+                    .expect("Transition input type should match its TypeId");
+                Effect::new(action(input))
+            }),
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn on_enter<F, E>(mut self, state: S, f: F) -> Self
+    where
+        F: Fn() -> E + Send + Sync + 'static,
+        E: EffectFn<M>,
+    {
+        self.on_enter
+            .push((state, Arc::new(move || Effect::new(f()))));
+        self
+    }
+
+    #[must_use]
+    pub fn on_exit<F, E>(mut self, state: S, f: F) -> Self
+    where
+        F: Fn() -> E + Send + Sync + 'static,
+        E: EffectFn<M>,
+    {
+        self.on_exit
+            .push((state, Arc::new(move || Effect::new(f()))));
+        self
+    }
+
+    #[must_use]
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    /// Apply `input` and queue the resulting effects on `effects_tx`.
+    #[track_caller]
+    pub fn fire<I: 'static>(
+        &mut self,
+        input: &I,
+        effects_tx: &EffectsTx<M>,
+    ) -> Option<Transitioned<S>> {
+        let origin = Location::caller();
+        let transition = self
+            .transitions
+            .iter()
+            .find(|t| t.input == TypeId::of::<I>() && t.from == self.state && (t.guard)(input))?
+            .clone();
+        let hooks = |hooks: &[(S, Hook<M>)], state: S| {
+            hooks
+                .iter()
+                .filter(move |(s, _)| *s == state)
+                .map(|(_, hook)| hook())
+                .collect::<Vec<_>>()
+        };
+        let effects = hooks(&self.on_exit, transition.from)
+            .into_iter()
+            .chain(std::iter::once((transition.action)(input)))
+            .chain(hooks(&self.on_enter, transition.to));
+        for effect in effects {
+            effects_tx.send_or_drop(effect.with_origin(origin));
+        }
+        self.state = transition.to;
+        Some(Transitioned {
+            from: transition.from,
+            to: transition.to,
+        })
+    }
+}
+
+impl<S: Copy, M: Model> Clone for StateMachine<S, M> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state,
+            transitions: self.transitions.clone(),
+            on_enter: self.on_enter.clone(),
+            on_exit: self.on_exit.clone(),
+        }
+    }
+}
+
+impl<S: fmt::Debug, M: Model> fmt::Debug for StateMachine<S, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateMachine")
+            .field("state", &self.state)
+            .field("transitions", &self.transitions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M: Model> Syzygy<M> {
+    /// Fire `input` at the state machine selected from the model.
+    #[track_caller]
+    pub fn transition<S, I, F>(&mut self, select: F, input: &I) -> Option<Transitioned<S>>
+    where
+        S: Copy + PartialEq + Send + Sync + 'static,
+        I: 'static,
+        F: FnOnce(&mut M) -> &mut StateMachine<S, M>,
+    {
+        let effects_tx = self.effects_bus.tx.clone();
+        select(self.model_mut()).fire(input, &effects_tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_state_machine() {
+        use crate::model::NoSnapshot;
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Conn {
+            Offline,
+            Online,
+        }
+
+        struct Connect(u32);
+
+        #[derive(Debug)]
+        struct App {
+            conn: StateMachine<Conn, App>,
+            log: Vec<String>,
+        }
+
+        impl NoSnapshot for App {}
+
+        let conn = StateMachine::new(Conn::Offline)
+            .on(
+                Conn::Offline,
+                Conn::Online,
+                |c: &Connect| c.0 > 0,
+                |c: &Connect| {
+                    let port = c.0;
+                    move |cx: &mut Syzygy<App>| cx.model_mut().log.push(format!("port {port}"))
+                },
+            )
+            .on_exit(Conn::Offline, || {
+                |cx: &mut Syzygy<App>| cx.model_mut().log.push("exit offline".into())
+            })
+            .on_enter(Conn::Online, || {
+                |cx: &mut Syzygy<App>| cx.model_mut().log.push("enter online".into())
+            });
+        let mut syzygy = Syzygy::builder()
+            .model(App {
+                conn,
+                log: Vec::new(),
+            })
+            .build();
+
+        assert_eq!(syzygy.transition(|m| &mut m.conn, &Connect(0)), None);
+        assert_eq!(
+            syzygy.transition(|m| &mut m.conn, &Connect(80)),
+            Some(Transitioned {
+                from: Conn::Offline,
+                to: Conn::Online,
+            })
+        );
+        assert_eq!(syzygy.transition(|m| &mut m.conn, &Connect(80)), None);
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().conn.state(), Conn::Online);
+        assert_eq!(
+            syzygy.model().log,
+            ["exit offline", "port 80", "enter online"]
+        );
+    }
+}
@@ -1,22 +1,272 @@
+use std::{
+    any::{Any, TypeId, type_name},
+    collections::VecDeque,
+    fmt,
+    panic::{self, AssertUnwindSafe, Location},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
 use bon::Builder;
+use rustc_hash::FxHashSet;
 
+#[cfg(feature = "devtools")]
+use crate::devtools::{BlockingDetector, BlockingEffect, QueueControl, WriteTimeline};
 use crate::{
+    actor::ActorStop,
     context::Context,
-    dispatch::{DispatchEffect, EffectsBus, EffectsTx},
-    model::{Model, ModelAccess, ModelModify, ModelSnapshotCreate},
-    resource::{ResourceAccess, ResourceModify, Resources},
+    dispatch::{
+        CapPolicy, CorrelationId, DispatchEffect, Effect, EffectFn, EffectMeta, EffectsBus,
+        EffectsTx, Lineage, TaskFailed, TaskFailedHandler,
+    },
+    lanes::Lanes,
+    model::{ComputedValues, Model, ModelAccess, ModelModify, ModelSnapshotCreate, ReadHandle},
+    plugin::Plugin,
+    resource::{
+        Provider, Random, ResourceAccess, ResourceChanged, ResourceGroup, ResourceModify,
+        Resources,
+        provide::{self, PendingProvider},
+    },
+    runtime::{
+        Inflight, InflightSite, OwnedRuntime, RuntimeAccess, RuntimeSpawner, Spawner, TaskInfo,
+    },
 };
 
 #[derive(Debug, Builder)]
+#[builder(state_mod(vis = "pub"), finish_fn(name = build_unchecked, vis = ""))]
 pub struct Syzygy<M: Model> {
     #[builder(field)]
     pub resources: Resources,
     #[builder(field)]
     pub effects_bus: EffectsBus<M>,
+    #[builder(field)]
+    audit_log: Option<AuditLog>,
+    #[builder(field)]
+    spawner: Spawner,
+    #[builder(field)]
+    overload: Option<Overload>,
+    #[builder(field)]
+    effect_hooks: EffectHooks,
+    #[builder(field)]
+    required_resources: Vec<(TypeId, &'static str)>,
+    #[builder(field)]
+    providers: Vec<PendingProvider>,
+    #[builder(field)]
+    lanes: Option<Lanes<M>>,
+    #[builder(field)]
+    time: Option<Time>,
+    #[builder(field)]
+    runtime_error: Option<std::io::Error>,
+    #[cfg(feature = "devtools")]
+    #[builder(field)]
+    blocking: Option<BlockingDetector>,
     #[cfg(feature = "parallel")]
     #[builder(into)]
     pub rayon_pool: RayonPool,
     pub model: M,
+    /// Keep draining the queue when an effect panics instead of unwinding.
+    #[builder(default)]
+    pub catch_effect_panics: bool,
+    #[builder(skip)]
+    read_handle: Option<ReadHandle<M>>,
+    #[builder(skip)]
+    shutdown_requested: bool,
+    #[builder(skip)]
+    correlation_id: Option<CorrelationId>,
+    #[builder(skip)]
+    tag: Option<&'static str>,
+    #[builder(skip)]
+    paused_tags: FxHashSet<&'static str>,
+    #[builder(skip)]
+    parked: Vec<Effect<M>>,
+    #[builder(skip)]
+    stats: Stats,
+    #[builder(skip)]
+    last_pending: usize,
+    #[builder(skip)]
+    pub(crate) actors: Vec<ActorStop>,
+    #[builder(skip)]
+    model_version: u64,
+    #[builder(skip)]
+    pub(crate) computed: ComputedValues<M>,
+    #[builder(skip)]
+    frame: Option<Frame>,
+    #[builder(skip)]
+    next_frame: Vec<Effect<M>>,
+    #[builder(skip)]
+    frames: u64,
+    #[builder(skip)]
+    immediate: VecDeque<Effect<M>>,
+    #[builder(skip)]
+    resumed: Vec<Effect<M>>,
+    #[cfg(feature = "devtools")]
+    #[builder(skip)]
+    pub(crate) model_writes: WriteTimeline,
+    #[cfg(feature = "devtools")]
+    #[builder(skip)]
+    pub(crate) queue_control: Arc<QueueControl<M>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BuildError {
+    #[error("resource `{0}` is already registered")]
+    DuplicateResource(&'static str),
+    #[error("required resource `{0}` is missing")]
+    MissingResource(&'static str),
+    #[error("spawner has no runtime to spawn onto")]
+    RuntimeUnavailable,
+    #[error("resource providers depend on each other: {0:?}")]
+    ProviderCycle(Vec<&'static str>),
+    #[error("owned runtime failed to start: {0}")]
+    OwnedRuntime(std::io::Error),
+}
+
+/// Outcome of a bounded `handle_effects` pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetReport {
+    pub processed: u64,
+    pub pending: usize,
+    pub elapsed: Duration,
+}
+
+impl BudgetReport {
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.pending > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    started: Instant,
+    processed: u64,
+}
+
+/// Clock resource kept up to date by `handle_effects` once the builder enabled
+/// `track_time`. Effects see the values of the pass they run in. Updates don't notify
+/// `on_resource_changed` listeners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    pub startup: Instant,
+    /// Start of the current `handle_effects` pass.
+    pub now: Instant,
+    /// Time since the start of the previous pass.
+    pub delta: Duration,
+    pub passes: u64,
+    /// Frames completed with `end_frame`.
+    pub frames: u64,
+}
+
+impl Time {
+    fn new(startup: Instant) -> Self {
+        Self {
+            startup,
+            now: startup,
+            delta: Duration::ZERO,
+            passes: 0,
+            frames: 0,
+        }
+    }
+
+    fn tick(&mut self, now: Instant, frames: u64) {
+        self.delta = now.saturating_duration_since(self.now);
+        self.now = now;
+        self.passes += 1;
+        self.frames = frames;
+    }
+
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.now.saturating_duration_since(self.startup)
+    }
+}
+
+/// Summary of one `begin_frame`/`end_frame` pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frame: u64,
+    pub effects_processed: u64,
+    /// `NextFrame` effects held back during the frame and requeued at its end.
+    pub deferred: usize,
+    pub elapsed: Duration,
+}
+
+/// Counters maintained by `handle_effects`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub effects_processed: u64,
+    pub batches: u64,
+    pub max_batch_size: u64,
+    pub effects_shed: u64,
+    /// Approximate bytes of effects left queued after the last batch.
+    pub queued_bytes: usize,
+    /// Effects dropped at dispatch by `memory_cap`.
+    pub effects_rejected: u64,
+}
+
+impl Stats {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn avg_batch_size(&self) -> f64 {
+        if self.batches == 0 {
+            0.0
+        } else {
+            self.effects_processed as f64 / self.batches as f64
+        }
+    }
+}
+
+/// Hook called with the metadata of every effect right before it runs.
+#[derive(Clone)]
+pub struct AuditLog(Arc<dyn Fn(&EffectMeta) + Send + Sync>);
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AuditLog").finish_non_exhaustive()
+    }
+}
+
+type StartHook = Arc<dyn Fn(&EffectMeta) + Send + Sync>;
+type EndHook = Arc<dyn Fn(&EffectMeta, Duration) + Send + Sync>;
+
+#[derive(Clone, Default)]
+struct EffectHooks {
+    on_start: Option<StartHook>,
+    on_end: Option<EndHook>,
+}
+
+impl fmt::Debug for EffectHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectHooks").finish_non_exhaustive()
+    }
+}
+
+/// Queue measurements passed to the `on_overload` callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverloadStats {
+    /// Queue length when the batch started.
+    pub pending: usize,
+    /// Queue length when the previous batch started.
+    pub previous_pending: usize,
+    /// Sheddable effects dropped during the batch.
+    pub shed: u64,
+}
+
+#[derive(Clone)]
+struct Overload {
+    threshold: usize,
+    callback: Arc<dyn Fn(&OverloadStats) + Send + Sync>,
+}
+
+impl fmt::Debug for Overload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Overload")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<M: Model, S: syzygy_builder::State> SyzygyBuilder<M, S> {
@@ -27,185 +277,1643 @@ impl<M: Model, S: syzygy_builder::State> SyzygyBuilder<M, S> {
         self.resources.insert(resource);
         self
     }
-}
 
-impl<M: Model> Syzygy<M> {
-    pub fn handle_effects(&mut self) {
-        while let Ok(effect) = self.effects_bus.rx.try_recv() {
-            (effect)(self);
+    /// Register every member of `group` as its own resource.
+    pub fn resource_group<G>(mut self, group: G) -> SyzygyBuilder<M, S>
+    where
+        G: ResourceGroup,
+    {
+        group.register(&mut self.resources);
+        self
+    }
+
+    /// Register a resource built from other resources, e.g. `|db: &Db, cfg: &Config| ..`.
+    /// Providers run at build time in dependency order, so registration order doesn't
+    /// matter, and are skipped if their output was registered explicitly. `build` panics
+    /// and `build_checked` fails if a provider's dependencies are missing or cyclic.
+    pub fn provide<P, Args>(mut self, provider: P) -> SyzygyBuilder<M, S>
+    where
+        P: Provider<Args>,
+    {
+        self.providers.push(PendingProvider::new(provider));
+        self
+    }
+
+    /// Seed the shared `Random` resource so runs draw reproducible sequences.
+    pub fn seed(self, seed: u64) -> SyzygyBuilder<M, S> {
+        self.resource(Random::seeded(seed))
+    }
+
+    /// Like `resource`, but fails instead of replacing an already registered `T`.
+    pub fn try_resource<T>(self, resource: T) -> Result<SyzygyBuilder<M, S>, BuildError>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        if self.resources.get_arc::<T>().is_some() {
+            return Err(BuildError::DuplicateResource(type_name::<T>()));
         }
+        Ok(self.resource(resource))
     }
-}
 
-impl<M: Model> Context for Syzygy<M> {
-    type Model = M;
-}
+    /// Make `build_checked` fail unless a `T` resource has been registered.
+    pub fn require_resource<T>(mut self) -> SyzygyBuilder<M, S>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.required_resources
+            .push((TypeId::of::<T>(), type_name::<T>()));
+        self
+    }
 
-impl<M: Model> ModelAccess for Syzygy<M> {
-    #[inline]
-    fn model(&self) -> &M {
-        &self.model
+    /// Register a resource shared through its `Arc`, for types that are not `Clone`.
+    pub fn resource_arc<T>(self, resource: Arc<T>) -> SyzygyBuilder<M, S>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.resources.insert_arc(resource);
+        self
+    }
+
+    /// Queue an effect to run on the first `handle_effects` call.
+    #[track_caller]
+    pub fn effect<F>(self, effect: F) -> SyzygyBuilder<M, S>
+    where
+        F: EffectFn<M>,
+    {
+        self.effects_bus.tx.send_or_drop(Effect::new(effect));
+        self
+    }
+
+    /// Replace the default tokio spawner used by `spawn`, `task` and schedules.
+    pub fn spawner<R>(mut self, spawner: R) -> SyzygyBuilder<M, S>
+    where
+        R: RuntimeSpawner,
+    {
+        self.spawner.set_runtime(spawner);
+        self
+    }
+
+    /// Spawn onto the runtime behind `handle` instead of the ambient one, so tasks can
+    /// be started from threads outside of it.
+    pub fn with_runtime_handle(self, handle: tokio::runtime::Handle) -> SyzygyBuilder<M, S> {
+        self.spawner(handle)
+    }
+
+    /// Spawn onto a runtime with `worker_threads` threads owned by this `Syzygy`. If the
+    /// runtime can't be started, `build` panics and `build_checked` fails.
+    pub fn with_owned_runtime(mut self, worker_threads: usize) -> SyzygyBuilder<M, S> {
+        match OwnedRuntime::new(worker_threads) {
+            Ok(runtime) => self.spawner(runtime),
+            Err(error) => {
+                self.runtime_error = Some(error);
+                self
+            }
+        }
+    }
+
+    /// Called right before each effect runs, e.g. to open a profiler scope.
+    pub fn on_effect_start<F>(mut self, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&EffectMeta) + Send + Sync + 'static,
+    {
+        self.effect_hooks.on_start = Some(Arc::new(f));
+        self
+    }
+
+    /// Called after each effect with the time it took to run.
+    pub fn on_effect_end<F>(mut self, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&EffectMeta, Duration) + Send + Sync + 'static,
+    {
+        self.effect_hooks.on_end = Some(Arc::new(f));
+        self
+    }
+
+    /// Called whenever a task or blocking job is handed to the spawner.
+    pub fn on_task_spawn<F>(mut self, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&TaskInfo) + Send + Sync + 'static,
+    {
+        self.spawner.hooks_mut().on_spawn = Some(Arc::new(f));
+        self
+    }
+
+    /// Called when a spawned task or blocking job finishes without panicking.
+    pub fn on_task_complete<F>(mut self, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&TaskInfo, Duration) + Send + Sync + 'static,
+    {
+        self.spawner.hooks_mut().on_complete = Some(Arc::new(f));
+        self
+    }
+
+    /// Count unfinished tasks per spawn site for `Syzygy::inflight_tasks`, and warn once
+    /// a site has more than `warn_threshold` of them.
+    pub fn track_inflight_tasks(mut self, warn_threshold: usize) -> SyzygyBuilder<M, S> {
+        self.spawner.inflight = Some(Arc::new(Inflight::new(warn_threshold)));
+        self
+    }
+
+    pub fn audit_log<F>(mut self, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&EffectMeta) + Send + Sync + 'static,
+    {
+        self.audit_log = Some(AuditLog(Arc::new(f)));
+        self
+    }
+
+    /// Treat a batch as overloaded when the queue holds more than `threshold` effects and
+    /// has grown since the previous batch. Sheddable effects are dropped during such a
+    /// batch and `f` is called afterwards.
+    pub fn on_overload<F>(mut self, threshold: usize, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&OverloadStats) + Send + Sync + 'static,
+    {
+        self.overload = Some(Overload {
+            threshold,
+            callback: Arc::new(f),
+        });
+        self
+    }
+
+    /// Maintain a `Time` resource, updated at the start of every `handle_effects` pass.
+    pub fn track_time(mut self) -> SyzygyBuilder<M, S> {
+        let time = Time::new(Instant::now());
+        self.time = Some(time);
+        self.resource(time)
+    }
+
+    /// Time every effect and report the ones running longer than `threshold` on the loop
+    /// thread, e.g. because of accidental file IO. Each is logged as a warning and
+    /// passed to `f`.
+    #[cfg(feature = "devtools")]
+    pub fn detect_blocking<F>(mut self, threshold: Duration, f: F) -> SyzygyBuilder<M, S>
+    where
+        F: Fn(&BlockingEffect) + Send + Sync + 'static,
+    {
+        self.blocking = Some(BlockingDetector::new(threshold, Arc::new(f)));
+        self
+    }
+
+    /// Schedule queued effects fairly across lanes instead of in arrival order. Each
+    /// lane, unlabeled effects included, takes turns running up to its weight (1 by
+    /// default) of effects. Label senders with `EffectsTx::with_lane`.
+    pub fn fair_lanes(mut self) -> SyzygyBuilder<M, S> {
+        self.lanes.get_or_insert_default();
+        self
+    }
+
+    /// Enable `fair_lanes` and let the `label` lane run `weight` effects per turn.
+    pub fn lane_weight(mut self, label: &'static str, weight: usize) -> SyzygyBuilder<M, S> {
+        self.lanes
+            .get_or_insert_default()
+            .set_weight(Some(label), weight);
+        self
+    }
+
+    /// Reject effects while the queued ones take more than `bytes`, as estimated from the
+    /// closure size plus any `Effect::with_size_hint`.
+    pub fn memory_cap(self, bytes: usize, policy: CapPolicy) -> SyzygyBuilder<M, S> {
+        self.effects_bus.tx.memory.set_cap(bytes, policy);
+        self
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn plugin<P>(self, plugin: P) -> SyzygyBuilder<M, S>
+    where
+        P: Plugin<M>,
+    {
+        plugin.build(self)
     }
 }
 
-impl<M: Model> ModelModify for Syzygy<M> {
-    #[inline]
-    fn model_mut(&mut self) -> &mut M {
-        &mut self.model
+impl<M: Model, S: syzygy_builder::IsComplete> SyzygyBuilder<M, S> {
+    /// Panics if a resource provider never could run or the owned runtime failed to
+    /// start, see `build_checked`.
+    pub fn build(self) -> Syzygy<M> {
+        let mut syzygy = self.build_unchecked();
+        if let Err(error) = syzygy
+            .check_runtime()
+            .and_then(|()| syzygy.resolve_providers())
+        {
+            panic!("Failed to build Syzygy: {error}");
+        }
+        syzygy
+    }
+
+    /// Like `build`, but returns errors instead of panicking, and also checks required
+    /// resources and that the spawner has a runtime.
+    pub fn build_checked(self) -> Result<Syzygy<M>, BuildError> {
+        let mut syzygy = self.build_unchecked();
+        syzygy.check_runtime()?;
+        syzygy.resolve_providers()?;
+        let resources = syzygy
+            .resources
+            .read()
+            .expect("Failed to acquire read lock");
+        if let Some((_, name)) = syzygy
+            .required_resources
+            .iter()
+            .find(|(ty, _)| !resources.contains_key(ty))
+        {
+            return Err(BuildError::MissingResource(name));
+        }
+        drop(resources);
+        if !syzygy.spawner.is_available() {
+            return Err(BuildError::RuntimeUnavailable);
+        }
+        Ok(syzygy)
     }
 }
 
-impl<M: Model> ModelSnapshotCreate for Syzygy<M> {
-    #[inline]
-    fn create_snapshot(&self) -> <<Self as Context>::Model as Model>::Snapshot {
-        self.model.to_snapshot()
+impl<M: Model> Syzygy<M> {
+    fn check_runtime(&mut self) -> Result<(), BuildError> {
+        self.runtime_error
+            .take()
+            .map_or(Ok(()), |error| Err(BuildError::OwnedRuntime(error)))
+    }
+
+    /// Run the builder's providers and report those that never could run.
+    fn resolve_providers(&mut self) -> Result<(), BuildError> {
+        provide::resolve(&mut self.providers, &self.resources);
+        if self.providers.is_empty() {
+            return Ok(());
+        }
+        let resources = self.resources.read().expect("Failed to acquire read lock");
+        let pending: FxHashSet<TypeId> = self.providers.iter().map(|p| p.output.0).collect();
+        let missing = self
+            .providers
+            .iter()
+            .flat_map(|p| &p.dependencies)
+            .find(|(ty, _)| !resources.contains_key(ty) && !pending.contains(ty));
+        Err(match missing {
+            Some((_, name)) => BuildError::MissingResource(name),
+            None => BuildError::ProviderCycle(self.providers.iter().map(|p| p.output.1).collect()),
+        })
     }
 }
 
-impl<M: Model> ResourceAccess for Syzygy<M> {
-    #[inline]
-    fn resources(&self) -> &Resources {
-        &self.resources
+impl<M: Model> Syzygy<M> {
+    pub fn handle_effects(&mut self) {
+        self.drain(|_| true);
+    }
+
+    /// Like `handle_effects`, but stops once `budget` has elapsed and leaves the rest queued.
+    pub fn handle_effects_with_budget(&mut self, budget: Duration) -> BudgetReport {
+        let start = Instant::now();
+        let processed = self.drain(|_| start.elapsed() < budget);
+        self.budget_report(processed, start)
+    }
+
+    /// Like `handle_effects`, but runs at most `max_effects` effects.
+    pub fn handle_effects_max(&mut self, max_effects: u64) -> BudgetReport {
+        let start = Instant::now();
+        let processed = self.drain(|processed| processed < max_effects);
+        self.budget_report(processed, start)
+    }
+
+    /// Like `handle_effects`, but checks `stop` between effects and returns once it is set.
+    pub fn handle_effects_until(&mut self, stop: &AtomicBool) -> BudgetReport {
+        let start = Instant::now();
+        let processed = self.drain(|_| !stop.load(Ordering::Acquire));
+        self.budget_report(processed, start)
+    }
+
+    fn budget_report(&self, processed: u64, start: Instant) -> BudgetReport {
+        BudgetReport {
+            processed,
+            pending: self.queued_len() + self.immediate.len() + self.resumed.len(),
+            elapsed: start.elapsed(),
+        }
+    }
+
+    fn queued_len(&self) -> usize {
+        self.effects_bus.rx.len() + self.lanes.as_ref().map_or(0, Lanes::len)
+    }
+
+    fn drain(&mut self, mut should_continue: impl FnMut(u64) -> bool) -> u64 {
+        if let Some(time) = &mut self.time {
+            time.tick(Instant::now(), self.frames);
+            self.resources.overwrite(*time);
+        }
+        for effect in self.resumed.drain(..) {
+            self.effects_bus.tx.requeue(effect);
+        }
+        let pending = self.queued_len();
+        let previous_pending = std::mem::replace(&mut self.last_pending, pending);
+        let overloaded = self
+            .overload
+            .as_ref()
+            .is_some_and(|overload| pending > overload.threshold && pending > previous_pending);
+        let mut processed = 0;
+        let mut shed = 0;
+        while should_continue(processed) {
+            let effect = if let Some(effect) = self.immediate.pop_front() {
+                effect
+            } else if let Some(effect) = self.next_queued() {
+                effect
+            } else {
+                break;
+            };
+            if overloaded && effect.meta.sheddable {
+                shed += 1;
+                continue;
+            }
+            if effect.meta.next_frame && self.frame.is_some() {
+                self.next_frame.push(effect);
+                continue;
+            }
+            if effect
+                .meta
+                .tag
+                .is_some_and(|tag| self.paused_tags.contains(tag))
+            {
+                self.parked.push(effect);
+                continue;
+            }
+            self.run_effect(effect, processed);
+            processed += 1;
+        }
+        if processed > 0 {
+            self.stats.effects_processed += processed;
+            self.stats.batches += 1;
+            self.stats.max_batch_size = self.stats.max_batch_size.max(processed);
+            if let Some(frame) = &mut self.frame {
+                frame.processed += processed;
+            }
+            self.publish_snapshot();
+        }
+        self.stats.queued_bytes = self.effects_bus.tx.memory.queued();
+        self.stats.effects_rejected = self.effects_bus.tx.memory.rejected();
+        if let Some(overload) = self.overload.as_ref().filter(|_| overloaded) {
+            log::warn!("Effect queue overloaded: {pending} pending, {shed} shed");
+            self.stats.effects_shed += shed;
+            (overload.callback)(&OverloadStats {
+                pending,
+                previous_pending,
+                shed,
+            });
+        }
+        processed
+    }
+
+    fn next_queued(&mut self) -> Option<Effect<M>> {
+        #[cfg(feature = "devtools")]
+        {
+            if !self.queue_control.may_run() {
+                return None;
+            }
+            if let Some(effect) = self.queue_control.pop_held() {
+                self.queue_control.took();
+                return Some(effect);
+            }
+        }
+        let effect = if let Some(lanes) = &mut self.lanes {
+            lanes.pull(&self.effects_bus.rx);
+            lanes.pop(&self.effects_bus.tx.memory)?
+        } else {
+            let effect = self.effects_bus.rx.try_recv().ok()?;
+            self.effects_bus.tx.memory.release(effect.meta.size);
+            effect
+        };
+        #[cfg(feature = "devtools")]
+        self.queue_control.took();
+        Some(effect)
+    }
+
+    /// Run `f` on the loop after every insertion or replacement of resource `T`.
+    #[track_caller]
+    pub fn on_resource_changed<T, F>(&self, f: F)
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Fn(&mut Syzygy<M>, &ResourceChanged<T>) + Send + Sync + 'static,
+    {
+        let tx = self.effects_bus.tx.clone();
+        let origin = Location::caller();
+        let f = Arc::new(f);
+        self.resources
+            .subscribe(move |change: &ResourceChanged<T>| {
+                let f = Arc::clone(&f);
+                let change = change.clone();
+                let effect =
+                    Effect::new(move |cx: &mut Syzygy<M>| f(cx, &change)).with_origin(origin);
+                let _ = tx.send(effect);
+            });
+    }
+
+    /// Handle errors of type `E` from `task_ok` futures. Replaces any earlier handler for `E`.
+    pub fn on_task_failed<E, F>(&self, f: F)
+    where
+        E: Send + Sync + 'static,
+        F: Fn(&mut Syzygy<M>, TaskFailed<E>) + Send + Sync + 'static,
+    {
+        self.add_resource(TaskFailedHandler::<M, E>(Arc::new(f)));
+    }
+
+    pub(crate) fn task_failed<E>(&mut self, failed: TaskFailed<E>)
+    where
+        E: fmt::Debug + Send + Sync + 'static,
+    {
+        if let Some(handler) = self.try_resource::<TaskFailedHandler<M, E>>() {
+            (handler.0)(self, failed);
+        } else {
+            log::error!(
+                "Task spawned at {} failed: {:?}",
+                failed.origin,
+                failed.error
+            );
+        }
+    }
+
+    /// Run `effect` right after the current effect, ahead of everything already queued.
+    /// Immediate effects run in the order they were added.
+    #[track_caller]
+    pub fn immediate<F>(&mut self, effect: F)
+    where
+        F: EffectFn<M>,
+    {
+        let effect = Effect::new(effect).with_lineage(Lineage::of(self));
+        self.immediate.push_back(effect);
+    }
+
+    /// Queue `effect` for the next `handle_effects` pass, keeping the current chain.
+    pub(crate) fn resume_next_pass(&mut self, effect: Effect<M>) {
+        let effect = effect.with_lineage(Lineage::of(self));
+        self.resumed.push(effect);
+    }
+
+    /// Start a frame. Until `end_frame`, effects dispatched with `dispatch_next_frame`
+    /// are held back instead of run.
+    pub fn begin_frame(&mut self) {
+        self.frame = Some(Frame {
+            started: Instant::now(),
+            processed: 0,
+        });
+    }
+
+    /// End the current frame and requeue its held-back effects for the next one.
+    pub fn end_frame(&mut self) -> FrameStats {
+        let frame = self
+            .frame
+            .take()
+            .expect("end_frame called without begin_frame");
+        self.frames += 1;
+        let deferred = self.next_frame.len();
+        for mut effect in self.next_frame.drain(..) {
+            effect.meta.next_frame = false;
+            self.effects_bus.tx.requeue(effect);
+        }
+        FrameStats {
+            frame: self.frames,
+            effects_processed: frame.processed,
+            deferred,
+            elapsed: frame.started.elapsed(),
+        }
+    }
+
+    #[must_use]
+    pub fn in_frame(&self) -> bool {
+        self.frame.is_some()
+    }
+
+    /// Bumped on every `model_mut`/`update`. Writes through the public field bypass it.
+    #[must_use]
+    pub fn model_version(&self) -> u64 {
+        self.model_version
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Unfinished tasks per spawn site, most first. Empty unless the builder enabled
+    /// `track_inflight_tasks`.
+    #[must_use]
+    pub fn inflight_tasks(&self) -> Vec<InflightSite> {
+        self.spawner
+            .inflight
+            .as_ref()
+            .map(|inflight| inflight.report())
+            .unwrap_or_default()
+    }
+
+    /// Approximate bytes of effects currently waiting in the queue.
+    #[must_use]
+    pub fn queued_bytes(&self) -> usize {
+        self.effects_bus.tx.memory.queued()
+    }
+
+    /// Park effects tagged with `tag` until `resume_tag` is called.
+    pub fn pause_tag(&mut self, tag: &'static str) {
+        self.paused_tags.insert(tag);
+    }
+
+    /// Requeue the effects parked under `tag`, keeping their dispatch order.
+    pub fn resume_tag(&mut self, tag: &'static str) {
+        if !self.paused_tags.remove(tag) {
+            return;
+        }
+        let (resumed, parked) = std::mem::take(&mut self.parked)
+            .into_iter()
+            .partition(|effect| effect.meta.tag == Some(tag));
+        self.parked = parked;
+        for effect in resumed {
+            self.effects_bus.tx.requeue(effect);
+        }
+    }
+
+    #[must_use]
+    pub fn is_tag_paused(&self, tag: &str) -> bool {
+        self.paused_tags.contains(tag)
+    }
+
+    #[must_use]
+    pub fn parked_effects(&self) -> usize {
+        self.parked.len()
+    }
+
+    /// Drive the effect queue, sleeping while it is empty, until `shutdown` is called.
+    pub async fn run_async(&mut self) {
+        self.shutdown_requested = false;
+        loop {
+            self.handle_effects();
+            if self.shutdown_requested {
+                break;
+            }
+            self.effects_bus.rx.notified().await;
+        }
+    }
+
+    /// Ask `run_async` to return once the current batch is done. Also stops spawned actors.
+    pub fn shutdown(&mut self) {
+        for actor in self.actors.drain(..) {
+            actor.stop();
+        }
+        self.shutdown_requested = true;
+        self.effects_bus.tx.wake();
+    }
+
+    #[must_use]
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    /// Returns a handle to the published snapshot. Publishing after each
+    /// `handle_effects` batch starts with the first call.
+    pub fn read_handle(&mut self) -> ReadHandle<M> {
+        self.read_handle
+            .get_or_insert_with(|| ReadHandle::new(self.model.to_snapshot()))
+            .clone()
+    }
+
+    fn run_effect(&mut self, effect: Effect<M>, position: u64) {
+        let (meta, effect) = effect.into_parts();
+        if let Some(audit_log) = &self.audit_log {
+            (audit_log.0)(&meta);
+        }
+        if let Some(on_start) = &self.effect_hooks.on_start {
+            on_start(&meta);
+        }
+        #[cfg(feature = "devtools")]
+        let timed = self.effect_hooks.on_end.is_some() || self.blocking.is_some();
+        #[cfg(not(feature = "devtools"))]
+        let timed = self.effect_hooks.on_end.is_some();
+        let started = timed.then(Instant::now);
+        self.correlation_id = meta.correlation_id;
+        self.tag = meta.tag;
+        #[cfg(feature = "devtools")]
+        self.model_writes.begin(&meta);
+        if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| (effect)(self))) {
+            let report = EffectPanic {
+                message: panic_message(payload.as_ref()).to_owned(),
+                origin: meta.origin,
+                tag: meta.tag,
+                correlation_id: meta.correlation_id,
+                position,
+            };
+            log::error!("{report}");
+            if !self.catch_effect_panics {
+                panic::resume_unwind(payload);
+            }
+        }
+        self.correlation_id = None;
+        self.tag = None;
+        #[cfg(feature = "devtools")]
+        self.model_writes.end();
+        if let (Some(on_end), Some(started)) = (&self.effect_hooks.on_end, started) {
+            on_end(&meta, started.elapsed());
+        }
+        #[cfg(feature = "devtools")]
+        if let (Some(blocking), Some(started)) = (&self.blocking, started) {
+            blocking.check(&meta, started.elapsed());
+        }
+    }
+
+    fn publish_snapshot(&self) {
+        if let Some(handle) = &self.read_handle {
+            handle.publish(self.model.to_snapshot());
+        }
+    }
+}
+
+impl<M: Model> Context for Syzygy<M> {
+    type Model = M;
+}
+
+impl<M: Model> ModelAccess for Syzygy<M> {
+    #[inline]
+    fn model(&self) -> &M {
+        &self.model
+    }
+}
+
+impl<M: Model> ModelModify for Syzygy<M> {
+    #[inline]
+    fn model_mut(&mut self) -> &mut M {
+        self.model_version = self.model_version.wrapping_add(1);
+        #[cfg(feature = "devtools")]
+        self.model_writes.record(self.model_version);
+        &mut self.model
+    }
+}
+
+impl<M: Model> ModelSnapshotCreate for Syzygy<M> {
+    #[inline]
+    fn create_snapshot(&self) -> <<Self as Context>::Model as Model>::Snapshot {
+        self.model.to_snapshot()
+    }
+}
+
+impl<M: Model> ResourceAccess for Syzygy<M> {
+    #[inline]
+    fn resources(&self) -> &Resources {
+        &self.resources
+    }
+}
+
+impl<M: Model> ResourceModify for Syzygy<M> {}
+
+impl<M: Model> RuntimeAccess for Syzygy<M> {
+    #[inline]
+    fn spawner(&self) -> &Spawner {
+        &self.spawner
+    }
+}
+
+impl<M: Model> DispatchEffect for Syzygy<M> {
+    #[inline]
+    fn effects_tx(&self) -> &EffectsTx<M> {
+        &self.effects_bus.tx
+    }
+
+    #[inline]
+    fn correlation_id(&self) -> Option<CorrelationId> {
+        self.correlation_id
+    }
+
+    #[inline]
+    fn tag(&self) -> Option<&'static str> {
+        self.tag
+    }
+}
+
+/// Panic raised by an effect, annotated with where the effect came from. Effect panics
+/// are logged with it, and then resumed with their original payload unless
+/// `catch_effect_panics`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectPanic {
+    pub message: String,
+    pub origin: &'static Location<'static>,
+    pub tag: Option<&'static str>,
+    pub correlation_id: Option<CorrelationId>,
+    /// Index of the effect within its `handle_effects` pass.
+    pub position: u64,
+}
+
+impl fmt::Display for EffectPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Effect dispatched at {} panicked at position {}",
+            self.origin, self.position
+        )?;
+        if let Some(tag) = self.tag {
+            write!(f, ", tag `{tag}`")?;
+        }
+        if let Some(correlation_id) = self.correlation_id {
+            write!(f, ", correlation id {}", correlation_id.get())?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+pub struct Deferred<F: FnOnce()>(Option<F>);
+
+impl<F: FnOnce()> Deferred<F> {
+    /// Drop without running the deferred function.
+    pub fn abort(mut self) {
+        self.0.take();
+    }
+}
+
+impl<F: FnOnce()> Drop for Deferred<F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.0.take() {
+            f();
+        }
+    }
+}
+
+/// Run the given function when the returned value is dropped (unless it's cancelled).
+#[must_use]
+pub fn defer<F: FnOnce()>(f: F) -> Deferred<F> {
+    Deferred(Some(f))
+}
+
+#[allow(clippy::items_after_statements)]
+#[allow(clippy::cast_precision_loss)]
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testing::fixtures::{TestModel, TestResource, increment};
+    use tokio::sync::oneshot;
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_model() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy = Syzygy::builder().model(model).build();
+
+        // Test initial state
+        let counter = syzygy.model().counter;
+        assert_eq!(counter, 0);
+
+        // Test model() access
+        assert_eq!(syzygy.model().counter, 0);
+
+        // Test model_mut() modification
+        syzygy.model_mut().counter += 1;
+        assert_eq!(syzygy.model().counter, 1);
+
+        // Test update
+        syzygy.update(|m| {
+            m.counter = 42;
+        });
+        assert_eq!(syzygy.model().counter, 42);
+
+        // Test query
+        let value = syzygy.query(|m| m.counter);
+        assert_eq!(value, 42);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_resources() {
+        let model = TestModel { counter: 0 };
+        let test_resource = TestResource {
+            name: "test_str".to_string(),
+        };
+        let syzygy = Syzygy::builder()
+            .model(model)
+            .resource(test_resource)
+            .build();
+
+        // Test accessing existing TestResource
+        assert_eq!(syzygy.resource::<TestResource>().name, "test_str");
+
+        // Test try_resource for existing resources
+        let test_resource = syzygy.try_resource::<TestResource>();
+        assert!(test_resource.is_some());
+        assert_eq!(test_resource.unwrap().name, "test_str");
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_no_snapshot_model_and_arc_resources() {
+        use crate::model::NoSnapshot;
+        use std::sync::Mutex;
+
+        #[derive(Debug, Default)]
+        struct Plain {
+            counter: i32,
+        }
+
+        impl NoSnapshot for Plain {}
+
+        struct Connection(Mutex<Vec<&'static str>>);
+
+        let mut syzygy = Syzygy::builder()
+            .model(Plain::default())
+            .resource_arc(Arc::new(Connection(Mutex::new(Vec::new()))))
+            .build();
+
+        syzygy.dispatch(|cx: &mut Syzygy<Plain>| {
+            cx.model_mut().counter += 1;
+            cx.resource_cached::<Connection>()
+                .0
+                .lock()
+                .unwrap()
+                .push("sent");
+        });
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 1);
+        let connection = syzygy.resource_cached::<Connection>();
+        assert_eq!(*connection.0.lock().unwrap(), ["sent"]);
+        let previous = syzygy.insert_arc(Arc::new(Connection(Mutex::new(Vec::new()))));
+        assert!(Arc::ptr_eq(&previous.unwrap(), &connection));
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_build_checked() {
+        let resource = || TestResource {
+            name: "test_str".to_string(),
+        };
+        let duplicate = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .resource(resource())
+            .try_resource(resource());
+        assert!(matches!(duplicate, Err(BuildError::DuplicateResource(_))));
+
+        let missing = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .require_resource::<TestResource>()
+            .build_checked();
+        assert!(matches!(missing, Err(BuildError::MissingResource(_))));
+
+        let no_runtime = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .try_resource(resource())
+            .unwrap()
+            .require_resource::<TestResource>()
+            .build_checked();
+        assert!(matches!(no_runtime, Err(BuildError::RuntimeUnavailable)));
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _guard = runtime.enter();
+        let syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .resource(resource())
+            .require_resource::<TestResource>()
+            .build_checked()
+            .unwrap();
+        assert_eq!(syzygy.resource::<TestResource>().name, "test_str");
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_async_dispatch() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        // Dispatch the effect multiple times
+        for _ in 0..5 {
+            syzygy.dispatch(|cx: &mut Syzygy<TestModel>| increment(cx));
+        }
+
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 5);
+    }
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_coalesced_dispatch() {
+        use crate::dispatch::CoalescingEffect;
+
+        struct Add(i32);
+
+        impl CoalescingEffect<TestModel> for Add {
+            fn merge(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+
+            fn apply(self, syzygy: &mut Syzygy<TestModel>) {
+                syzygy.model_mut().counter += self.0;
+            }
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        for i in 1..=100 {
+            syzygy.dispatch_coalesced(Add(i));
+        }
+        assert_eq!(syzygy.effects_bus.rx.len(), 1);
+
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 5050);
+
+        syzygy.dispatch_coalesced(Add(1));
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 5051);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_coalesced_slot_freed_when_dropped() {
+        use crate::dispatch::CoalescingEffect;
+
+        struct Add(i32);
+
+        impl CoalescingEffect<TestModel> for Add {
+            fn merge(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+
+            fn apply(self, syzygy: &mut Syzygy<TestModel>) {
+                syzygy.model_mut().counter += self.0;
+            }
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .memory_cap(0, CapPolicy::RejectAll)
+            .build();
+
+        syzygy.dispatch_coalesced(Add(1));
+        syzygy
+            .effects_bus
+            .tx
+            .memory
+            .set_cap(usize::MAX, CapPolicy::RejectAll);
+        syzygy.dispatch_coalesced(Add(2));
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 2);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_audit_log_correlation() {
+        use std::sync::Mutex;
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&log);
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .audit_log(move |meta| {
+                sink.lock()
+                    .unwrap()
+                    .push((meta.origin.file(), meta.correlation_id));
+            })
+            .build();
+
+        let id = CorrelationId::new();
+        syzygy.dispatch_correlated(id, |cx: &mut Syzygy<TestModel>| {
+            cx.dispatch(increment);
+            cx.task(|cx| async move { cx.dispatch(increment) });
+        });
+        syzygy.dispatch(increment);
+
+        for _ in 0..3 {
+            syzygy.handle_effects();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let log = log.lock().unwrap();
+        assert_eq!(syzygy.model().counter, 3);
+        assert_eq!(log.len(), 5);
+        assert!(log.iter().all(|(file, _)| file.ends_with("syzygy.rs")));
+        let correlated = log.iter().filter(|(_, c)| *c == Some(id)).count();
+        assert_eq!(correlated, 4);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_tag_inherited_by_follow_ups() {
+        use std::sync::Mutex;
+
+        let tags = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&tags);
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .audit_log(move |meta| sink.lock().unwrap().push(meta.tag))
+            .build();
+
+        syzygy.dispatch_tagged("net", |cx: &mut Syzygy<TestModel>| {
+            cx.dispatch(increment);
+            cx.immediate(increment);
+            cx.task(|cx| async move { cx.dispatch(increment) });
+        });
+        syzygy.dispatch(increment);
+        for _ in 0..3 {
+            syzygy.handle_effects();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let tags = tags.lock().unwrap();
+        assert_eq!(syzygy.model().counter, 4);
+        assert_eq!(tags.iter().filter(|tag| **tag == Some("net")).count(), 5);
+        assert_eq!(tags.iter().filter(|tag| tag.is_none()).count(), 1);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_catch_effect_panics() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .catch_effect_panics(true)
+            .build();
+
+        syzygy.dispatch(|_: &mut Syzygy<TestModel>| panic!("boom"));
+        syzygy.dispatch(increment);
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 1);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_effect_panic_annotated() {
+        #[derive(Debug, PartialEq)]
+        struct Boom(u32);
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.dispatch(increment);
+        syzygy.dispatch(|_: &mut Syzygy<TestModel>| panic::panic_any(Boom(7)));
+        let payload =
+            panic::catch_unwind(AssertUnwindSafe(|| syzygy.handle_effects())).unwrap_err();
+        assert_eq!(payload.downcast_ref::<Boom>(), Some(&Boom(7)));
+
+        let origin = Location::caller();
+        let report = EffectPanic {
+            message: "boom".to_string(),
+            origin,
+            tag: Some("net"),
+            correlation_id: None,
+            position: 1,
+        };
+        assert_eq!(
+            report.to_string(),
+            format!("Effect dispatched at {origin} panicked at position 1, tag `net`: boom")
+        );
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_pause_tag_parks_task_continuation() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.dispatch_tagged("network", |cx: &mut Syzygy<TestModel>| {
+            cx.task_ok(
+                |_| async { Ok::<_, ()>(1) },
+                |cx, n| cx.model_mut().counter += n,
+            );
+        });
+        syzygy.handle_effects();
+        syzygy.pause_tag("network");
+        while syzygy.queued_len() == 0 {
+            tokio::task::yield_now().await;
+        }
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 0);
+        assert_eq!(syzygy.parked_effects(), 1);
+
+        syzygy.resume_tag("network");
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_pause_tag() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.pause_tag("network");
+        syzygy.dispatch_tagged("network", |cx: &mut Syzygy<TestModel>| {
+            cx.model_mut().counter *= 10;
+        });
+        syzygy.dispatch_tagged("ui", increment);
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 1);
+        assert_eq!(syzygy.parked_effects(), 1);
+
+        syzygy.resume_tag("network");
+        syzygy.handle_effects();
+
+        assert!(!syzygy.is_tag_paused("network"));
+        assert_eq!(syzygy.parked_effects(), 0);
+        assert_eq!(syzygy.model().counter, 10);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_stats() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        for _ in 0..4 {
+            syzygy.dispatch(increment);
+        }
+        syzygy.handle_effects();
+        syzygy.handle_effects();
+        for _ in 0..2 {
+            syzygy.dispatch(increment);
+        }
+        syzygy.handle_effects();
+
+        let stats = syzygy.stats();
+        assert_eq!(stats.effects_processed, 6);
+        assert_eq!(stats.batches, 2);
+        assert_eq!(stats.max_batch_size, 4);
+        assert!((stats.avg_batch_size() - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_overload_shedding() {
+        use std::sync::Mutex;
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let reports_clone = Arc::clone(&reports);
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder()
+            .model(model)
+            .on_overload(4, move |stats| reports_clone.lock().unwrap().push(*stats))
+            .build();
+
+        for _ in 0..3 {
+            syzygy.dispatch_sheddable(increment);
+        }
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 3);
+
+        for _ in 0..4 {
+            syzygy.dispatch(increment);
+            syzygy.dispatch_sheddable(increment);
+        }
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 7);
+        assert_eq!(syzygy.stats().effects_shed, 4);
+        assert_eq!(
+            *reports.lock().unwrap(),
+            [OverloadStats {
+                pending: 8,
+                previous_pending: 3,
+                shed: 4,
+            }]
+        );
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_frame_sync() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.begin_frame();
+        syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
+            increment(cx);
+            cx.dispatch_next_frame(|cx: &mut Syzygy<TestModel>| cx.model_mut().counter *= 10);
+        });
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+        let stats = syzygy.end_frame();
+        assert_eq!(
+            (stats.frame, stats.effects_processed, stats.deferred),
+            (1, 1, 1)
+        );
+
+        syzygy.begin_frame();
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 10);
+        assert_eq!(syzygy.end_frame().deferred, 0);
     }
-}
 
-impl<M: Model> ResourceModify for Syzygy<M> {}
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_immediate() {
+        let model = TestModel { counter: 1 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
 
-impl<M: Model> DispatchEffect for Syzygy<M> {
-    #[inline]
-    fn effects_tx(&self) -> &EffectsTx<M> {
-        &self.effects_bus.tx
+        syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
+            cx.dispatch(|cx: &mut Syzygy<TestModel>| cx.model_mut().counter += 1);
+            cx.immediate(|cx| {
+                cx.model_mut().counter *= 10;
+                cx.immediate(|cx| cx.model_mut().counter *= 10);
+            });
+        });
+        syzygy.dispatch(|cx: &mut Syzygy<TestModel>| cx.model_mut().counter += 3);
+        syzygy.handle_effects();
+
+        assert_eq!(syzygy.model().counter, 104);
     }
-}
 
-pub struct Deferred<F: FnOnce()>(Option<F>);
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_handle_effects_until() {
+        let mut syzygy = Syzygy::builder().model(TestModel { counter: 0 }).build();
+        let stop = Arc::new(AtomicBool::new(false));
+        for _ in 0..5 {
+            let stop = Arc::clone(&stop);
+            syzygy.dispatch(move |syzygy: &mut Syzygy<TestModel>| {
+                syzygy.model_mut().counter += 1;
+                if syzygy.model().counter == 2 {
+                    stop.store(true, Ordering::Release);
+                }
+            });
+        }
 
-impl<F: FnOnce()> Deferred<F> {
-    /// Drop without running the deferred function.
-    pub fn abort(mut self) {
-        self.0.take();
+        let report = syzygy.handle_effects_until(&stop);
+        assert_eq!((report.processed, report.pending), (2, 3));
+
+        stop.store(false, Ordering::Release);
+        syzygy.handle_effects_until(&stop);
+        assert_eq!(syzygy.model().counter, 5);
     }
-}
 
-impl<F: FnOnce()> Drop for Deferred<F> {
-    fn drop(&mut self) {
-        if let Some(f) = self.0.take() {
-            f();
-        }
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_memory_cap() {
+        let mut syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .memory_cap(1024, CapPolicy::RejectSheddable)
+            .build();
+        let blob = vec![0_u8; 600];
+        let len = blob.len();
+        let sent: Vec<_> = (0..3)
+            .map(|_| {
+                let blob = blob.clone();
+                let effect = Effect::new(move |syzygy: &mut Syzygy<TestModel>| {
+                    syzygy.model_mut().counter += i32::from(blob[0]) + 1;
+                });
+                syzygy
+                    .effects_bus
+                    .tx
+                    .send(effect.with_size_hint(len).sheddable())
+            })
+            .collect();
+        assert!(sent[0].is_ok());
+        assert!(matches!(
+            sent[1],
+            Err(crate::dispatch::SendError::Rejected(_))
+        ));
+        syzygy.dispatch(increment);
+        assert!(syzygy.queued_bytes() > 600);
+
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 2);
+        assert_eq!(syzygy.stats().effects_rejected, 2);
+        assert_eq!(syzygy.stats().queued_bytes, 0);
     }
-}
 
-/// Run the given function when the returned value is dropped (unless it's cancelled).
-#[must_use]
-pub fn defer<F: FnOnce()>(f: F) -> Deferred<F> {
-    Deferred(Some(f))
-}
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_time_resource() {
+        use std::sync::atomic::AtomicUsize;
 
-#[allow(clippy::items_after_statements)]
-#[allow(clippy::cast_precision_loss)]
-#[cfg(test)]
-mod tests {
+        let mut syzygy = Syzygy::builder()
+            .model(TestModel { counter: 0 })
+            .track_time()
+            .build();
+        assert_eq!(syzygy.resource::<Time>().passes, 0);
 
-    use super::*;
+        syzygy.handle_effects();
+        std::thread::sleep(Duration::from_millis(5));
+        syzygy.begin_frame();
+        syzygy.end_frame();
+        syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
+            let time = cx.resource::<Time>();
+            assert_eq!((time.passes, time.frames), (2, 1));
+            assert!(time.delta >= Duration::from_millis(5));
+            cx.model_mut().counter += 1;
+        });
+        syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
+        assert!(syzygy.resource::<Time>().elapsed() >= Duration::from_millis(5));
 
-    #[derive(Debug, Clone)]
-    struct TestModel {
-        counter: i32,
+        let changes = Arc::new(AtomicUsize::new(0));
+        syzygy.on_resource_changed::<Time, _>({
+            let changes = Arc::clone(&changes);
+            move |_, _| {
+                changes.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        syzygy.handle_effects();
+        syzygy.handle_effects();
+        assert_eq!(changes.load(Ordering::SeqCst), 0);
+        assert_eq!(syzygy.queued_len(), 0);
+        assert_eq!(syzygy.resource::<Time>().passes, 4);
     }
 
-    impl Model for TestModel {
-        type Snapshot = Self;
-        fn to_snapshot(&self) -> Self::Snapshot {
-            self.clone()
-        }
-    }
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test]
+    async fn test_handle_effects_budget() {
+        use std::time::Duration;
 
-    #[derive(Debug, Clone)]
-    struct TestResource {
-        name: String,
-    }
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
 
-    fn increment(syzygy: &mut Syzygy<TestModel>) {
-        syzygy.model_mut().counter += 1;
+        for _ in 0..10 {
+            syzygy.dispatch(increment);
+        }
+        let report = syzygy.handle_effects_max(4);
+        assert_eq!(report.processed, 4);
+        assert_eq!(report.pending, 6);
+        assert!(report.is_exhausted());
+
+        syzygy.dispatch(|_: &mut Syzygy<TestModel>| {
+            std::thread::sleep(Duration::from_millis(20));
+        });
+        let report = syzygy.handle_effects_with_budget(Duration::from_millis(5));
+        assert_eq!(report.pending, 0);
+        assert_eq!(syzygy.model().counter, 10);
+
+        for _ in 0..3 {
+            syzygy.dispatch(|_: &mut Syzygy<TestModel>| {
+                std::thread::sleep(Duration::from_millis(20));
+            });
+        }
+        let report = syzygy.handle_effects_with_budget(Duration::from_millis(5));
+        assert_eq!(report.processed, 1);
+        assert_eq!(report.pending, 2);
     }
 
     #[cfg(not(feature = "parallel"))]
     #[tokio::test]
-    async fn test_model() {
+    async fn test_sync_dispatch() {
         let model = TestModel { counter: 0 };
-        let mut syzygy = Syzygy::builder().model(model).build();
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
 
-        // Test initial state
-        let counter = syzygy.model().counter;
-        assert_eq!(counter, 0);
+        let rx = syzygy.dispatch_sync(increment);
 
-        // Test model() access
-        assert_eq!(syzygy.model().counter, 0);
+        syzygy.handle_effects();
+        rx.await.unwrap();
 
-        // Test model_mut() modification
-        syzygy.model_mut().counter += 1;
         assert_eq!(syzygy.model().counter, 1);
+    }
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dispatch_with_result() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
 
-        // Test update
-        syzygy.update(|m| {
-            m.counter = 42;
+        let rx = syzygy.dispatch_with_result(|cx: &mut Syzygy<TestModel>| {
+            increment(cx);
+            cx.model().counter * 10
         });
-        assert_eq!(syzygy.model().counter, 42);
+        syzygy.handle_effects();
+        assert_eq!(rx.await.unwrap(), 10);
 
-        // Test query
-        let value = syzygy.query(|m| m.counter);
-        assert_eq!(value, 42);
+        let (done_tx, done_rx) = oneshot::channel();
+        syzygy.task(|cx| async move {
+            let counter = cx
+                .dispatch_await(|cx: &mut Syzygy<TestModel>| {
+                    increment(cx);
+                    cx.model().counter
+                })
+                .await
+                .unwrap();
+            done_tx.send(counter).unwrap();
+        });
+        for _ in 0..3 {
+            syzygy.handle_effects();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(done_rx.await.unwrap(), 2);
     }
 
     #[cfg(not(feature = "parallel"))]
-    #[tokio::test]
-    async fn test_resources() {
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dispatch_blocking() {
+        use std::time::Duration;
+
         let model = TestModel { counter: 0 };
-        let test_resource = TestResource {
-            name: "test_str".to_string(),
-        };
-        let syzygy = Syzygy::builder()
-            .model(model)
-            .resource(test_resource)
-            .build();
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
 
-        // Test accessing existing TestResource
-        assert_eq!(syzygy.resource::<TestResource>().name, "test_str");
+        let (done_tx, done_rx) = oneshot::channel();
+        syzygy.spawn(move |cx| {
+            let counter = cx.dispatch_blocking(
+                |syzygy: &mut Syzygy<TestModel>| {
+                    increment(syzygy);
+                    syzygy.model().counter
+                },
+                Duration::from_secs(5),
+            );
+            let timed_out = cx.dispatch_blocking(
+                |_| std::thread::sleep(Duration::from_millis(20)),
+                Duration::from_millis(1),
+            );
+            let _ = done_tx.send((counter, timed_out));
+        });
 
-        // Test try_resource for existing resources
-        let test_resource = syzygy.try_resource::<TestResource>();
-        assert!(test_resource.is_some());
-        assert_eq!(test_resource.unwrap().name, "test_str");
+        tokio::pin!(done_rx);
+        let (counter, timed_out) = loop {
+            syzygy.handle_effects();
+            tokio::select! {
+                result = &mut done_rx => break result.unwrap(),
+                () = tokio::time::sleep(Duration::from_millis(5)) => {}
+            }
+        };
+        assert_eq!(counter, Ok(1));
+        assert_eq!(timed_out, Err(crossbeam_channel::RecvTimeoutError::Timeout));
     }
 
     #[cfg(not(feature = "parallel"))]
-    #[tokio::test]
-    async fn test_async_dispatch() {
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_context_update() {
         let model = TestModel { counter: 0 };
         let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
 
-        // Dispatch the effect multiple times
-        for _ in 0..5 {
-            syzygy.dispatch(|cx: &mut Syzygy<TestModel>| increment(cx));
+        let (done_tx, done_rx) = oneshot::channel();
+        syzygy.task(|cx| async move {
+            cx.update(|m| m.counter += 1).await.unwrap();
+            let counter = cx
+                .update_and_query(|m| m.counter *= 5, |m| m.counter)
+                .await
+                .unwrap();
+            done_tx.send(counter).unwrap();
+        });
+        for _ in 0..4 {
+            syzygy.handle_effects();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
         }
 
+        assert_eq!(done_rx.await.unwrap(), 5);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_ok() {
+        use crate::dispatch::TaskFailed;
+        use std::time::Duration;
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        syzygy.on_task_failed(|cx: &mut Syzygy<TestModel>, failed: TaskFailed<String>| {
+            assert_eq!(failed.error, "boom");
+            cx.model_mut().counter += 10;
+        });
+
+        syzygy.task_ok(
+            |_| async { Ok::<_, String>(2) },
+            |cx: &mut Syzygy<TestModel>, n| cx.model_mut().counter += n,
+        );
+        syzygy.task_ok(
+            |_| async { Err::<i32, _>("boom".to_string()) },
+            |cx: &mut Syzygy<TestModel>, n| cx.model_mut().counter += n,
+        );
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while syzygy.model().counter < 12 {
+                syzygy.handle_effects();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(syzygy.model().counter, 12);
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_if_unchanged() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let (fresh_tx, mut fresh_rx) = oneshot::channel();
+        let (stale_tx, stale_rx) = oneshot::channel();
+
+        syzygy.task_if_unchanged(
+            |m: &TestModel| m.counter,
+            |_| async move {
+                let _ = stale_rx.await;
+                100
+            },
+            |cx: &mut Syzygy<TestModel>, n| cx.model_mut().counter = n,
+        );
+        syzygy.handle_effects();
+        syzygy.dispatch(increment);
+        syzygy.handle_effects();
+        let _ = stale_tx.send(());
+        while syzygy.queued_len() == 0 {
+            tokio::task::yield_now().await;
+        }
         syzygy.handle_effects();
+        assert_eq!(syzygy.model().counter, 1);
 
-        assert_eq!(syzygy.model().counter, 5);
+        syzygy.task_if_unchanged(
+            |m: &TestModel| m.counter,
+            |_| async { 42 },
+            move |cx: &mut Syzygy<TestModel>, n| {
+                cx.model_mut().counter += n;
+                let _ = fresh_tx.send(());
+            },
+        );
+        while fresh_rx.try_recv().is_err() {
+            syzygy.handle_effects();
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(syzygy.model().counter, 43);
     }
+
     #[cfg(not(feature = "parallel"))]
-    #[tokio::test]
-    async fn test_sync_dispatch() {
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_with_deadline() {
+        use crate::dispatch::Deadline;
+        use std::time::Duration;
+
         let model = TestModel { counter: 0 };
         let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+        let (result_tx, result_rx) = oneshot::channel();
+        syzygy.task_with_deadline(
+            |_| async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                7
+            },
+            Duration::from_millis(5),
+            move |_, output| {
+                let _ = result_tx.send(output);
+            },
+            |cx: &mut Syzygy<TestModel>| cx.model_mut().counter = -1,
+        );
 
-        let rx = syzygy.dispatch_sync(increment);
+        tokio::pin!(result_rx);
+        let output = loop {
+            syzygy.handle_effects();
+            tokio::select! {
+                output = &mut result_rx => break output.unwrap(),
+                () = tokio::time::sleep(Duration::from_millis(5)) => {}
+            }
+        };
+        assert_eq!(output, Deadline::Late(7));
+        assert_eq!(syzygy.model().counter, -1);
+    }
 
-        syzygy.handle_effects();
-        rx.await.unwrap();
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_task_with_deadline_spawner_sleep() {
+        use crate::{
+            dispatch::Deadline,
+            runtime::{BoxFuture, RuntimeSpawner, TokioSpawner},
+        };
+        use std::time::Duration;
 
-        assert_eq!(syzygy.model().counter, 1);
+        /// Tokio spawner whose timer never fires.
+        struct NoTimer;
+
+        impl RuntimeSpawner for NoTimer {
+            fn spawn(&self, future: BoxFuture) {
+                TokioSpawner.spawn(future);
+            }
+
+            fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+                TokioSpawner.spawn_blocking(f);
+            }
+
+            fn sleep(&self, _duration: Duration) -> BoxFuture {
+                Box::pin(std::future::pending())
+            }
+        }
+
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).spawner(NoTimer).build();
+        let (result_tx, result_rx) = oneshot::channel();
+        syzygy.task_with_deadline(
+            |_| async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                7
+            },
+            Duration::from_millis(1),
+            move |_, output| {
+                let _ = result_tx.send(output);
+            },
+            |cx: &mut Syzygy<TestModel>| cx.model_mut().counter = -1,
+        );
+
+        tokio::pin!(result_rx);
+        let output = loop {
+            syzygy.handle_effects();
+            tokio::select! {
+                output = &mut result_rx => break output.unwrap(),
+                () = tokio::time::sleep(Duration::from_millis(5)) => {}
+            }
+        };
+        assert_eq!(output, Deadline::OnTime(7));
+        assert_eq!(syzygy.model().counter, 0);
     }
+
     #[cfg(not(feature = "parallel"))]
     #[tokio::test(flavor = "multi_thread")]
     async fn test_thread_task() {
@@ -262,6 +1970,28 @@ mod tests {
         assert_eq!(syzygy.model().counter, 2);
     }
 
+    #[cfg(not(feature = "parallel"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_async() {
+        let model = TestModel { counter: 0 };
+        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
+
+        syzygy.task(|cx| async move {
+            for _ in 0..3 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                cx.dispatch(increment);
+            }
+            cx.dispatch(|cx: &mut Syzygy<TestModel>| cx.shutdown());
+        });
+
+        tokio::time::timeout(std::time::Duration::from_secs(1), syzygy.run_async())
+            .await
+            .unwrap();
+
+        assert!(syzygy.is_shutdown_requested());
+        assert_eq!(syzygy.model().counter, 3);
+    }
+
     // // #[test]
     // // #[cfg(not(feature = "async"))]
     // // fn test_app_context_query() {
@@ -658,175 +2388,7 @@ mod tests {
     // //     handle.join().unwrap();
     // //     assert!(!cx.is_running());
     // // }
-    #[cfg(not(feature = "parallel"))]
-    #[tokio::test]
-    async fn test_increment_dispatch() {
-        let model = TestModel { counter: 0 };
-        let mut syzygy = Syzygy::builder().model(model).build();
-
-        const ITERATIONS: usize = 1_000_000;
-
-        for _ in 0..ITERATIONS {
-            syzygy.dispatch(increment);
-        }
-        syzygy.handle_effects();
-
-        assert_eq!(syzygy.model().counter, ITERATIONS as i32);
-    }
     // #[ignore]
-    #[cfg(not(feature = "parallel"))]
-    #[tokio::test]
-    async fn test_dispatch_performance() {
-        use std::time::Instant;
-
-        let model = TestModel { counter: 0 };
-        let mut syzygy = Syzygy::builder().model(model.clone()).build();
-
-        const ITERATIONS: usize = 1_000_000;
-        const RUNS: usize = 10;
-
-        // Test one chain of empty effects
-        let mut best_empty = std::time::Duration::from_secs(u64::MAX);
-        for _ in 0..RUNS {
-            let start = Instant::now();
-            for _ in 0..ITERATIONS {
-                syzygy.dispatch(|_: &mut Syzygy<TestModel>| {});
-            }
-            syzygy.handle_effects();
-            let duration = start.elapsed();
-            best_empty = best_empty.min(duration);
-        }
-        let ops_empty = ITERATIONS as f64 / best_empty.as_secs_f64();
-
-        // Test multiple small chains of empty effects
-        let mut best_chain = std::time::Duration::from_secs(u64::MAX);
-        for _ in 0..RUNS {
-            let start = Instant::now();
-            for _ in 0..ITERATIONS {
-                syzygy.dispatch(|_: &mut Syzygy<TestModel>| {});
-                syzygy.dispatch(|_: &mut Syzygy<TestModel>| {});
-                syzygy.dispatch(|_: &mut Syzygy<TestModel>| {});
-                syzygy.dispatch(|_: &mut Syzygy<TestModel>| {});
-                syzygy.handle_effects();
-            }
-            let duration = start.elapsed();
-            best_chain = best_chain.min(duration);
-        }
-        let ops_chain = ITERATIONS as f64 / best_chain.as_secs_f64();
-
-        // Test model mutation effects
-        let mut best_mutation = std::time::Duration::from_secs(u64::MAX);
-        for _ in 0..RUNS {
-            let start = Instant::now();
-            for _ in 0..ITERATIONS {
-                syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
-                    cx.model_mut().counter += 1;
-                });
-            }
-            syzygy.handle_effects();
-            let duration = start.elapsed();
-            best_mutation = best_mutation.min(duration);
-        }
-        let ops_mutation = ITERATIONS as f64 / best_mutation.as_secs_f64();
-
-        // Test chained effects
-        let mut best_chained = std::time::Duration::from_secs(u64::MAX);
-        for _ in 0..RUNS {
-            let start = Instant::now();
-            for _ in 0..ITERATIONS {
-                syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                    cx.dispatch(|cx| cx.model_mut().counter += 1);
-                });
-            }
-            syzygy.handle_effects();
-            let duration = start.elapsed();
-            best_chained = best_chained.min(duration);
-        }
-        let ops_chained = ITERATIONS as f64 / best_chained.as_secs_f64();
-
-        // Real scenario
-        let mut best_real = std::time::Duration::from_secs(u64::MAX);
-        for _ in 0..RUNS {
-            let start = Instant::now();
-            for _ in 0..ITERATIONS {
-                syzygy.dispatch(|cx: &mut Syzygy<TestModel>| {
-                    // Simulate a more complex real-world scenario with
-                    // multiple branching effects and state updates
-
-                    // Update some base state
-                    cx.model_mut().counter += 1;
-
-                    // Chain some conditional effects
-                    if cx.model().counter % 2 == 0 {
-                        cx.model_mut().counter += 2;
-                        cx.dispatch(|cx| {
-                            cx.model_mut().counter *= 2;
-                        });
-                    } else {
-                        cx.model_mut().counter -= 1;
-                        cx.dispatch(|cx| {
-                            cx.model_mut().counter += 5;
-                        });
-                    }
-
-                    // Another conditional branch
-                    cx.dispatch(|cx| {
-                        if cx.model().counter > 100 {
-                            cx.model_mut().counter = 0;
-                        }
-                    });
-
-                    // Simulate some expensive computation
-                    cx.dispatch(|cx| {
-                        let mut total = 0;
-                        for i in 0..cx.model().counter {
-                            total += i;
-                        }
-                        cx.model_mut().counter = total;
-                    });
-                });
-            }
-            syzygy.handle_effects();
-            let duration = start.elapsed();
-            best_real = best_real.min(duration);
-        }
-        let ops_real = ITERATIONS as f64 / best_real.as_secs_f64();
-
-        println!("Performance Results:");
-        println!("-------------------");
-        println!("Empty effects:");
-        println!("  Iterations: {ITERATIONS}");
-        println!("  Time: {best_empty:?}");
-        println!("  Speed: {ops_empty:.2} ops/sec");
-        println!();
-        println!("Mutation effects:");
-        println!("  Iterations: {ITERATIONS}");
-        println!("  Time: {best_mutation:?}");
-        println!("  Speed: {ops_mutation:.2} ops/sec");
-        println!();
-        println!("Big chain of empty effects:");
-        println!("  Iterations: {ITERATIONS}");
-        println!("  Time: {best_chained:?}");
-        println!("  Speed: {ops_chained:.2} ops/sec");
-        println!();
-        println!("Small chains of empty effects:");
-        println!("  Iterations: {ITERATIONS}");
-        println!("  Time: {best_chain:?}");
-        println!("  Speed: {ops_chain:.2} ops/sec");
-        println!();
-        println!("Real scenario:");
-        println!("  Iterations: {ITERATIONS}");
-        println!("  Time: {best_real:?}");
-        println!("  Speed: {ops_real:.2} ops/sec");
-        println!();
-    }
     // #[cfg(not(feature = "parallel"))]
     // #[tokio::test]
     // async fn test_task_performance() {
@@ -858,36 +2420,4 @@ mod tests {
     //         "Task dispatch: {ITERATIONS} iterations in {best_dispatch:?} ({ops_dispatch:.2} ops/sec)"
     //     );
     // }
-    #[cfg(all(not(feature = "async"), not(feature = "parallel")))]
-    #[tokio::test]
-    async fn benchmark_direct_model_update() {
-        use std::time::Instant;
-
-        let model = TestModel { counter: 0 };
-        let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(model).build();
-
-        const ITERATIONS: usize = 1_000_000;
-        const RUNS: usize = 10;
-
-        let mut best_duration = std::time::Duration::from_secs(u64::MAX);
-
-        for _ in 0..RUNS {
-            let start = Instant::now();
-
-            for _ in 0..ITERATIONS {
-                syzygy.update(|m| m.counter += 1);
-            }
-
-            let duration = start.elapsed();
-            best_duration = best_duration.min(duration);
-        }
-
-        let ops_per_sec = ITERATIONS as f64 / best_duration.as_secs_f64();
-
-        println!(
-            "Direct model update benchmark:\n\
-             {ITERATIONS} iterations in {best_duration:?} (best of {RUNS} runs)\n\
-             {ops_per_sec:.2} ops/sec",
-        );
-    }
 }
@@ -0,0 +1,25 @@
+use crate::{
+    model::{Model, ModelModify},
+    syzygy::Syzygy,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TestModel {
+    pub(crate) counter: i32,
+}
+
+impl Model for TestModel {
+    type Snapshot = Self;
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TestResource {
+    pub(crate) name: String,
+}
+
+pub(crate) fn increment(syzygy: &mut Syzygy<TestModel>) {
+    syzygy.model_mut().counter += 1;
+}
@@ -0,0 +1,169 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use bon::Builder;
+
+use crate::{
+    dispatch::{DispatchEffect, Effect, SendError},
+    model::Model,
+    syzygy::Syzygy,
+};
+
+/// Parameters for `run`. The same seed always produces the same per-producer choices.
+#[derive(Debug, Clone, Builder)]
+pub struct StressConfig {
+    #[builder(default = 4)]
+    pub producers: usize,
+    #[builder(default = 1_000)]
+    pub effects_per_producer: u64,
+    #[builder(default = 0x5eed)]
+    pub seed: u64,
+    /// Give up and report a deadlock if the queue has not drained by then.
+    #[builder(default = Duration::from_secs(10))]
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StressReport {
+    pub expected: u64,
+    pub received: u64,
+    /// Effects that ran before an effect dispatched earlier by the same producer.
+    pub out_of_order: u64,
+    pub deadlocked: bool,
+    pub elapsed: Duration,
+}
+
+impl StressReport {
+    #[must_use]
+    pub fn lost(&self) -> u64 {
+        self.expected.saturating_sub(self.received)
+    }
+
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.lost() == 0 && self.out_of_order == 0 && !self.deadlocked
+    }
+
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        assert!(self.is_ok(), "queue invariants violated: {self:?}");
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct StressModel {
+    next: Vec<u64>,
+    received: u64,
+    out_of_order: u64,
+}
+
+impl Model for StressModel {
+    type Snapshot = ();
+    fn to_snapshot(&self) -> Self::Snapshot {}
+}
+
+impl StressModel {
+    fn record(&mut self, producer: usize, seq: u64) {
+        self.received += 1;
+        if seq != self.next[producer] {
+            self.out_of_order += 1;
+        }
+        self.next[producer] = seq + 1;
+    }
+}
+
+/// Xorshift, good enough to vary interleavings without a rand dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Hammer one loop from `producers` threads, each dispatching a numbered sequence of
+/// effects with randomized yields and dispatch styles, while the loop drains in
+/// randomly sized bounded batches. Checks that no effect is lost, that each
+/// producer's effects run in dispatch order and that draining finishes in time.
+#[must_use]
+pub fn run(config: &StressConfig) -> StressReport {
+    let start = Instant::now();
+    let mut syzygy = Syzygy::builder()
+        .model(StressModel {
+            next: vec![0; config.producers],
+            ..StressModel::default()
+        })
+        .build();
+
+    let producers: Vec<_> = (0..config.producers)
+        .map(|producer| {
+            let tx = syzygy.effects_tx().clone();
+            let mut rng = Rng(config.seed.wrapping_add(producer as u64 + 1) | 1);
+            let count = config.effects_per_producer;
+            thread::spawn(move || {
+                for seq in 0..count {
+                    let roll = rng.next();
+                    let record = move |cx: &mut Syzygy<StressModel>| {
+                        cx.model.record(producer, seq);
+                    };
+                    let effect = if roll.is_multiple_of(3) {
+                        // Goes through the immediate queue of a carrier effect.
+                        Effect::new(move |cx: &mut Syzygy<StressModel>| cx.immediate(record))
+                    } else {
+                        Effect::new(record)
+                    };
+                    if matches!(tx.send(effect), Err(SendError::Disconnected(_))) {
+                        return;
+                    }
+                    if roll.is_multiple_of(7) {
+                        thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+
+    let mut rng = Rng(config.seed | 1);
+    let expected = config.producers as u64 * config.effects_per_producer;
+    let mut deadlocked = false;
+    while syzygy.model.received < expected {
+        if start.elapsed() > config.timeout {
+            deadlocked = true;
+            break;
+        }
+        syzygy.handle_effects_max(rng.next() % 64 + 1);
+        if rng.next().is_multiple_of(5) {
+            thread::yield_now();
+        }
+    }
+    for producer in producers {
+        let _ = producer.join();
+    }
+    syzygy.handle_effects();
+
+    StressReport {
+        expected,
+        received: syzygy.model.received,
+        out_of_order: syzygy.model.out_of_order,
+        deadlocked,
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "parallel"))]
+    #[test]
+    fn test_stress_queue() {
+        let report = run(&StressConfig::builder().effects_per_producer(500).build());
+        report.assert_ok();
+        assert_eq!(report.received, 2_000);
+    }
+}
@@ -0,0 +1,268 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Data, DeriveInput, Error, Fields, GenericArgument, Ident, Member, PathArguments, Token, Type,
+    parse_quote, punctuated::Punctuated,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Resources,
+    Dispatcher,
+    Spawner,
+}
+
+const EXPECTED_ROLE: &str =
+    "expected `resources`, `dispatcher`, `spawner`, `resource` or `model_field = ...`";
+
+/// How a plain field is filled in by the generated `FromContext`.
+enum Lookup {
+    Default,
+    Resource,
+    ModelField(Punctuated<Member, Token![.]>),
+}
+
+struct ContextField<'a> {
+    ident: &'a Ident,
+    ty: &'a Type,
+    role: Option<Role>,
+    lookup: Lookup,
+}
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "`Context` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new_spanned(
+            input,
+            "`Context` can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut model: Option<Type> = None;
+    for attr in &input.attrs {
+        if attr.path().is_ident("context") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("model") {
+                    model = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `model = ...`"))
+                }
+            })?;
+        }
+    }
+
+    let mut fields = Vec::with_capacity(named.named.len());
+    for field in &named.named {
+        let mut role = None;
+        let mut lookup = Lookup::Default;
+        for attr in &field.attrs {
+            if !attr.path().is_ident("context") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("resource") || meta.path.is_ident("model_field") {
+                    if role.is_some() || !matches!(lookup, Lookup::Default) {
+                        return Err(meta.error("field already has a context role"));
+                    }
+                    lookup = if meta.path.is_ident("resource") {
+                        Lookup::Resource
+                    } else {
+                        Lookup::ModelField(
+                            meta.value()?.call(Punctuated::parse_separated_nonempty)?,
+                        )
+                    };
+                    return Ok(());
+                }
+                let parsed = if meta.path.is_ident("resources") {
+                    Role::Resources
+                } else if meta.path.is_ident("dispatcher") {
+                    Role::Dispatcher
+                } else if meta.path.is_ident("spawner") {
+                    Role::Spawner
+                } else {
+                    return Err(meta.error(EXPECTED_ROLE));
+                };
+                if role.replace(parsed).is_some() || !matches!(lookup, Lookup::Default) {
+                    return Err(meta.error("field already has a context role"));
+                }
+                Ok(())
+            })?;
+        }
+        fields.push(ContextField {
+            ident: field.ident.as_ref().expect("named field"),
+            ty: &field.ty,
+            role,
+            lookup,
+        });
+    }
+
+    let resources = unique_field(input, &fields, Role::Resources, "resources")?;
+    let dispatcher = unique_field(input, &fields, Role::Dispatcher, "dispatcher")?;
+    let spawner = unique_field(input, &fields, Role::Spawner, "spawner")?;
+
+    let model = match model {
+        Some(model) => model,
+        None => dispatcher
+            .and_then(|field| first_type_argument(field.ty))
+            .cloned()
+            .ok_or_else(|| {
+                Error::new_spanned(
+                    input,
+                    "missing `#[context(model = ...)]` and no `#[context(dispatcher)]` to infer it from",
+                )
+            })?,
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut tokens = quote! {
+        impl #impl_generics ::syzygy::context::Context for #name #ty_generics #where_clause {
+            type Model = #model;
+        }
+    };
+
+    if let Some(field) = resources {
+        let ident = field.ident;
+        tokens.extend(quote! {
+            impl #impl_generics ::syzygy::resource::ResourceAccess for #name #ty_generics #where_clause {
+                #[inline]
+                fn resources(&self) -> &::syzygy::resource::Resources {
+                    &self.#ident
+                }
+            }
+        });
+    }
+
+    if let Some(field) = dispatcher {
+        let ident = field.ident;
+        tokens.extend(quote! {
+            impl #impl_generics ::syzygy::dispatch::DispatchEffect for #name #ty_generics #where_clause {
+                #[inline]
+                fn effects_tx(&self) -> &::syzygy::dispatch::EffectsTx<#model> {
+                    &self.#ident
+                }
+            }
+        });
+    }
+
+    if let Some(field) = spawner {
+        let ident = field.ident;
+        tokens.extend(quote! {
+            impl #impl_generics ::syzygy::runtime::RuntimeAccess for #name #ty_generics #where_clause {
+                #[inline]
+                fn spawner(&self) -> &::syzygy::runtime::Spawner {
+                    &self.#ident
+                }
+            }
+        });
+    }
+
+    tokens.extend(from_context(input, &fields, &model));
+    Ok(tokens)
+}
+
+fn from_context(input: &DeriveInput, fields: &[ContextField<'_>], model: &Type) -> TokenStream {
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let mut generics = input.generics.clone();
+    generics
+        .params
+        .push(parse_quote!(__C: ::syzygy::context::Context<Model = #model>));
+    let where_clause = generics.make_where_clause();
+    let mut inits = Vec::with_capacity(fields.len());
+    for field in fields {
+        let ident = field.ident;
+        let init = match field.role {
+            Some(Role::Resources) => {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(__C: ::syzygy::resource::ResourceAccess));
+                quote!(::core::clone::Clone::clone(
+                    ::syzygy::resource::ResourceAccess::resources(context)
+                ))
+            }
+            Some(Role::Dispatcher) => {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(__C: ::syzygy::dispatch::DispatchEffect));
+                quote!(::core::clone::Clone::clone(
+                    ::syzygy::dispatch::DispatchEffect::effects_tx(context)
+                ))
+            }
+            Some(Role::Spawner) => {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(__C: ::syzygy::runtime::RuntimeAccess));
+                quote!(::core::clone::Clone::clone(
+                    ::syzygy::runtime::RuntimeAccess::spawner(context)
+                ))
+            }
+            None => match &field.lookup {
+                Lookup::Default => quote!(::core::default::Default::default()),
+                Lookup::Resource => {
+                    let ty = field.ty;
+                    where_clause
+                        .predicates
+                        .push(parse_quote!(__C: ::syzygy::resource::ResourceAccess));
+                    quote!(::syzygy::resource::ResourceAccess::resource::<#ty>(context))
+                }
+                Lookup::ModelField(path) => {
+                    where_clause
+                        .predicates
+                        .push(parse_quote!(__C: ::syzygy::model::ModelAccess));
+                    quote!(::core::clone::Clone::clone(
+                        &::syzygy::model::ModelAccess::model(context).#path
+                    ))
+                }
+            },
+        };
+        inits.push(quote!(#ident: #init));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::syzygy::context::FromContext<__C> for #name #ty_generics #where_clause {
+            fn from_context(context: &__C) -> Self {
+                Self { #(#inits),* }
+            }
+        }
+    }
+}
+
+fn unique_field<'f, 'a>(
+    input: &DeriveInput,
+    fields: &'f [ContextField<'a>],
+    role: Role,
+    name: &str,
+) -> syn::Result<Option<&'f ContextField<'a>>> {
+    let mut found = fields.iter().filter(|field| field.role == Some(role));
+    let first = found.next();
+    if found.next().is_some() {
+        return Err(Error::new_spanned(
+            input,
+            format!("only one field can be marked `#[context({name})]`"),
+        ));
+    }
+    Ok(first)
+}
+
+fn first_type_argument(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let PathArguments::AngleBracketed(args) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
@@ -0,0 +1,82 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields};
+
+const MAX_FIELDS: usize = 64;
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "`DirtyTrack` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new_spanned(
+            input,
+            "`DirtyTrack` can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut dirty = None;
+    let mut tracked = Vec::with_capacity(named.named.len());
+    for field in &named.named {
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("named field should have an ident");
+        if field.attrs.iter().any(|attr| attr.path().is_ident("dirty")) {
+            if dirty.is_some() {
+                return Err(Error::new_spanned(field, "duplicate `#[dirty]` field"));
+            }
+            dirty = Some(ident);
+        } else {
+            tracked.push((ident, &field.ty, &field.vis));
+        }
+    }
+    let Some(dirty) = dirty else {
+        return Err(Error::new_spanned(
+            input,
+            "`DirtyTrack` needs a `#[dirty]` field of type `syzygy::model::Dirty`",
+        ));
+    };
+    if tracked.len() > MAX_FIELDS {
+        return Err(Error::new_spanned(
+            input,
+            "`DirtyTrack` supports at most 64 tracked fields",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let field_names = tracked.iter().map(|(ident, _, _)| ident.to_string());
+    let accessors = tracked.iter().enumerate().map(|(bit, (ident, ty, vis))| {
+        let setter = format_ident!("set_{ident}");
+        let mutator = format_ident!("{ident}_mut");
+        quote! {
+            #vis fn #setter(&mut self, value: #ty) {
+                self.#dirty.mark(#bit);
+                self.#ident = value;
+            }
+
+            #vis fn #mutator(&mut self) -> &mut #ty {
+                self.#dirty.mark(#bit);
+                &mut self.#ident
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::syzygy::model::DirtyTrack for #name #ty_generics #where_clause {
+            const FIELDS: &'static [&'static str] = &[#(#field_names),*];
+
+            fn dirty_mut(&mut self) -> &mut ::syzygy::model::Dirty {
+                &mut self.#dirty
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    })
+}
@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "`ResourceGroup` can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new_spanned(
+            input,
+            "`ResourceGroup` can only be derived for structs with named fields",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`ResourceGroup` can't be derived for generic structs",
+        ));
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let access = format_ident!("{name}Access");
+    let idents: Vec<_> = named
+        .named
+        .iter()
+        .map(|field| {
+            field
+                .ident
+                .as_ref()
+                .expect("named field should have an ident")
+        })
+        .collect();
+    let types: Vec<_> = named.named.iter().map(|field| &field.ty).collect();
+    let doc = format!("Typed accessors for the resources registered by `{name}`.");
+
+    Ok(quote! {
+        impl ::syzygy::resource::ResourceGroup for #name {
+            fn register(self, resources: &mut ::syzygy::resource::Resources) {
+                #(resources.insert(self.#idents);)*
+            }
+
+            fn from_resources(resources: &::syzygy::resource::Resources) -> Option<Self> {
+                Some(Self {
+                    #(#idents: resources.get::<#types>()?,)*
+                })
+            }
+        }
+
+        #[doc = #doc]
+        #vis trait #access: ::syzygy::resource::ResourceAccess {
+            #(
+                fn #idents(&self) -> #types {
+                    self.resource::<#types>()
+                }
+            )*
+        }
+
+        impl<C: ::syzygy::resource::ResourceAccess> #access for C {}
+    })
+}
@@ -0,0 +1,46 @@
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+mod context;
+mod dirty;
+mod group;
+
+/// Derives `Context`, `FromContext` and the access traits backed by annotated fields.
+///
+/// Fields are picked up with `#[context(resources)]`, `#[context(dispatcher)]` and
+/// `#[context(spawner)]`. The model is taken from `#[context(model = T)]` on the
+/// struct, or inferred from the dispatcher's `EffectsTx<T>`.
+///
+/// Other fields are cloned from a resource with `#[context(resource)]`, from the
+/// model with `#[context(model_field = a.b)]`, or left to `Default`.
+#[proc_macro_derive(Context, attributes(context))]
+pub fn derive_context(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    context::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `DirtyTrack` for a struct with one `#[dirty]` field of type `Dirty`.
+///
+/// Every other field gets a `set_<field>` and a `<field>_mut` method, with the field's
+/// visibility, that mark it dirty. `Syzygy::take_dirty` returns and clears the set.
+#[proc_macro_derive(DirtyTrack, attributes(dirty))]
+pub fn derive_dirty_track(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    dirty::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `ResourceGroup`, registering every field as its own resource.
+///
+/// Also generates a `<Name>Access` trait, implemented for every `ResourceAccess`
+/// context, with one accessor per field returning that resource.
+#[proc_macro_derive(ResourceGroup)]
+pub fn derive_resource_group(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    group::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
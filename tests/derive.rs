@@ -0,0 +1,152 @@
+use std::time::Duration;
+
+use syzygy::{
+    dispatch::EffectsTx,
+    model::{Dirty, DirtyTrack, Model, NoSnapshot},
+    prelude::*,
+    resource::ResourceGroup,
+    runtime::Spawner,
+    scheduler::{ScheduleEffect, every},
+};
+
+#[derive(Debug, Clone)]
+struct TestModel {
+    counter: i32,
+}
+
+impl Model for TestModel {
+    type Snapshot = Self;
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TestResource {
+    name: String,
+}
+
+#[derive(Context)]
+struct WorkerContext {
+    #[context(resources)]
+    resources: Resources,
+    #[context(dispatcher)]
+    effects_tx: EffectsTx<TestModel>,
+    #[context(spawner)]
+    spawner: Spawner,
+    label: String,
+}
+
+#[derive(Context)]
+#[context(model = TestModel)]
+struct ReadOnlyContext {
+    #[context(resources)]
+    resources: Resources,
+}
+
+#[derive(Context)]
+#[context(model = TestModel)]
+struct HandlerDeps {
+    #[context(resource)]
+    config: TestResource,
+    #[context(model_field = counter)]
+    counter: i32,
+}
+
+fn increment(syzygy: &mut Syzygy<TestModel>) {
+    syzygy.model_mut().counter += 1;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_derive_context() {
+    let model = TestModel { counter: 0 };
+    let mut syzygy = Syzygy::builder()
+        .model(model)
+        .resource(TestResource {
+            name: "derived".to_string(),
+        })
+        .build();
+
+    let cx = WorkerContext::from_context(&syzygy);
+    assert!(cx.label.is_empty());
+    assert_eq!(cx.resource::<TestResource>().name, "derived");
+    cx.dispatch(increment);
+    cx.schedule(every(Duration::from_millis(5)).times(1), || increment);
+
+    let read_only = ReadOnlyContext::from_context(&cx);
+    assert_eq!(read_only.resource::<TestResource>().name, "derived");
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    syzygy.handle_effects();
+    assert_eq!(syzygy.model().counter, 2);
+
+    let deps = HandlerDeps::from_context(&syzygy);
+    assert_eq!(deps.config.name, "derived");
+    assert_eq!(deps.counter, 2);
+}
+
+#[derive(Debug, DirtyTrack)]
+struct TrackedModel {
+    title: String,
+    count: u32,
+    #[dirty]
+    dirty: Dirty,
+}
+
+impl NoSnapshot for TrackedModel {}
+
+#[tokio::test]
+async fn test_dirty_track() {
+    let model = TrackedModel {
+        title: String::new(),
+        count: 0,
+        dirty: Dirty::default(),
+    };
+    let mut syzygy = Syzygy::builder().model(model).build();
+    syzygy.dispatch(|syzygy: &mut Syzygy<TrackedModel>| {
+        *syzygy.model_mut().count_mut() += 1;
+    });
+    syzygy.handle_effects();
+
+    let dirty = syzygy.take_dirty();
+    assert!(dirty.contains("count"));
+    assert!(!dirty.contains("title"));
+    assert!(syzygy.take_dirty().is_empty());
+
+    syzygy.update(|model| model.set_title("done".to_string()));
+    assert_eq!(syzygy.take_dirty().iter().collect::<Vec<_>>(), ["title"]);
+    assert_eq!(syzygy.model().count, 1);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Endpoint(String);
+
+#[derive(Debug, ResourceGroup)]
+struct AppServices {
+    config: TestResource,
+    endpoint: Endpoint,
+    retries: u32,
+}
+
+#[tokio::test]
+async fn test_resource_group() {
+    let syzygy = Syzygy::builder()
+        .model(TestModel { counter: 0 })
+        .resource_group(AppServices {
+            config: TestResource {
+                name: "grouped".to_string(),
+            },
+            endpoint: Endpoint("localhost".to_string()),
+            retries: 3,
+        })
+        .build();
+
+    assert_eq!(syzygy.config().name, "grouped");
+    assert_eq!(syzygy.endpoint(), Endpoint("localhost".to_string()));
+    assert_eq!(syzygy.retries(), 3);
+
+    let services = AppServices::from_resources(&syzygy.resources).unwrap();
+    assert_eq!(services.retries, 3);
+    syzygy.remove_resource::<u32>();
+    assert!(AppServices::from_resources(&syzygy.resources).is_none());
+}
@@ -0,0 +1,31 @@
+//! Raises real signals at its own process, so it runs in its own test binary.
+#![cfg(all(unix, not(feature = "parallel")))]
+
+use std::time::Duration;
+
+use syzygy::{model::Model, prelude::*};
+
+#[derive(Debug, Clone)]
+struct TestModel;
+
+impl Model for TestModel {
+    type Snapshot = Self;
+    fn to_snapshot(&self) -> Self::Snapshot {
+        self.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_shutdown_on_signal() {
+    let mut syzygy: Syzygy<TestModel> = Syzygy::builder().model(TestModel).build();
+    syzygy.shutdown_on_signal().unwrap();
+
+    std::process::Command::new("kill")
+        .args(["-TERM", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), syzygy.run_async())
+        .await
+        .expect("run_async should stop on SIGTERM");
+    assert!(syzygy.is_shutdown_requested());
+}